@@ -0,0 +1,22 @@
+-- Keep a record of approved vouches so they can be shown on profiles
+CREATE TABLE vouches (
+    vouch_id INT AUTO_INCREMENT PRIMARY KEY,         -- Unique ID for each vouch
+    user_id BIGINT NOT NULL,                         -- Discord user ID of the vouched member
+    vouched_by BIGINT NOT NULL,                      -- Discord user ID of the member who vouched
+    approved_by BIGINT NOT NULL,                     -- Discord user ID of the admin who approved the vouch
+    vouched_at TIMESTAMP NOT NULL,                   -- When the vouch was submitted
+    approved_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, -- When the vouch was approved
+    INDEX (user_id),                                 -- Index for efficient lookup by user_id
+    INDEX (vouched_by)                               -- Index for efficient lookup by vouched_by
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Record every action (hug, kiss, pat...) performed between members
+CREATE TABLE action_events (
+    event_id INT AUTO_INCREMENT PRIMARY KEY,         -- Unique ID for each action event
+    actor_id BIGINT NOT NULL,                        -- Discord user ID of the member performing the action
+    target_id BIGINT NOT NULL,                       -- Discord user ID of the member receiving the action
+    action VARCHAR(64) NOT NULL,                     -- The action performed, e.g. "hug"
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, -- When the action was performed
+    INDEX (actor_id),                                -- Index for efficient lookup by actor_id
+    INDEX (target_id)                                -- Index for efficient lookup by target_id
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
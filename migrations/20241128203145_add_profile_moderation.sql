@@ -0,0 +1,4 @@
+-- Allow admins to lock profiles from edits and force actions off for a user
+ALTER TABLE users
+ADD COLUMN locked_until TIMESTAMP NULL DEFAULT NULL,
+ADD COLUMN actions_forced_off TINYINT NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- Badges earned by users through bot activity
+CREATE TABLE user_badges (
+    user_id BIGINT NOT NULL,                         -- Discord user ID of the member who earned the badge
+    badge_id VARCHAR(64) NOT NULL,                   -- ID of the badge, as defined in the bot's badge registry
+    earned_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, -- When the badge was earned
+    PRIMARY KEY (user_id, badge_id)                  -- A badge can only be earned once per user
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
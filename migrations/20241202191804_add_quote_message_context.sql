@@ -0,0 +1,8 @@
+-- Store where a quote came from, so it can be linked back to and rendered in full
+ALTER TABLE quotes
+ADD COLUMN guild_id BIGINT NULL,                     -- Discord guild ID the message was sent in
+ADD COLUMN channel_id BIGINT NULL,                   -- Discord channel ID the message was sent in
+ADD COLUMN message_id BIGINT NULL,                   -- Discord message ID of the quoted message
+ADD COLUMN attachments TEXT NULL,                    -- Attachment URLs of the message, one per line
+ADD COLUMN reply_username VARCHAR(255) NULL,         -- Username of the author of the message being replied to
+ADD COLUMN reply_content TEXT NULL;                  -- Content of the message being replied to
@@ -0,0 +1,6 @@
+-- Remove any quotes of the same message that were stored more than once, keeping the oldest
+DELETE newer FROM quotes newer
+JOIN quotes older ON newer.message_id = older.message_id AND newer.quote_id > older.quote_id;
+
+-- A message can only be quoted once
+ALTER TABLE quotes ADD UNIQUE INDEX quotes_message_id_unique (message_id);
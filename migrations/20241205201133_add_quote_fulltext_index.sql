@@ -0,0 +1,2 @@
+-- Allow searching quotes by their text
+ALTER TABLE quotes ADD FULLTEXT INDEX quotes_quote_fulltext (quote);
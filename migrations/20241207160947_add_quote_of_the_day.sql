@@ -0,0 +1,8 @@
+-- History of the daily quote, used to avoid repeats and to skip or reroll today's quote
+CREATE TABLE quote_of_the_day (
+    day DATE NOT NULL PRIMARY KEY,                   -- The day, in the configured timezone
+    quote_id INT NULL,                               -- The quote posted that day, NULL if the day was skipped
+    message_id BIGINT NULL,                          -- Discord message ID of the post, if one was made
+    posted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, -- When the quote was (last) picked
+    INDEX (quote_id)                                 -- Index for efficient lookup of when a quote was last shown
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,8 @@
+-- Upvotes on quotes, one per member per quote
+CREATE TABLE quote_votes (
+    quote_id INT NOT NULL,                           -- The quote that was upvoted
+    user_id BIGINT NOT NULL,                         -- Discord user ID of the member who upvoted it
+    voted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, -- When the vote was cast
+    PRIMARY KEY (quote_id, user_id),                 -- A member can only upvote a quote once
+    INDEX (user_id)                                  -- Index for efficient removal of a member's votes
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
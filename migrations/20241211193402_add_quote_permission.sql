@@ -0,0 +1,3 @@
+-- Let members choose who may quote them: 0 = nobody, 1 = vouched members only, 2 = everyone
+ALTER TABLE users
+ADD COLUMN quotes_allowed TINYINT NOT NULL DEFAULT 2;
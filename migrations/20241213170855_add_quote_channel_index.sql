@@ -0,0 +1,3 @@
+-- Index for efficient filtering of quotes by the channel they were said in
+ALTER TABLE quotes
+ADD INDEX (channel_id);
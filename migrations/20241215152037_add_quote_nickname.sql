@@ -0,0 +1,3 @@
+-- Store the server nickname of the quoted member, shown if they are no longer in the server
+ALTER TABLE quotes
+ADD COLUMN nickname VARCHAR(255) NULL;              -- Server nickname of the author when the message was quoted
@@ -0,0 +1,3 @@
+-- Let members turn off whole categories of actions, e.g. romantic ones, while keeping the rest
+ALTER TABLE users
+ADD COLUMN blocked_action_categories TEXT NULL; -- Comma separated category names
@@ -0,0 +1,8 @@
+-- Temporary bans from using actions, handed out automatically to members who keep hitting the
+-- action rate limits
+CREATE TABLE action_bans (
+    user_id BIGINT PRIMARY KEY,                      -- Discord user ID of the banned member
+    banned_until TIMESTAMP NOT NULL,                 -- When the ban runs out
+    reason VARCHAR(255) NOT NULL,                    -- Why the member was banned
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP -- When the ban was handed out
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,15 @@
+-- Keep profile moderation apart from the profile, so deleting the profile or leaving and
+-- rejoining the server doesn't lift a lock or an actions override
+CREATE TABLE profile_moderation (
+    user_id BIGINT PRIMARY KEY,                      -- Discord user ID of the moderated member
+    locked_until TIMESTAMP NULL DEFAULT NULL,        -- Until when the about and pronouns are locked from edits
+    actions_forced_off TINYINT NOT NULL DEFAULT 0    -- Whether an admin turned actions off for the member
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT INTO profile_moderation (user_id, locked_until, actions_forced_off)
+SELECT CAST(discord_id AS UNSIGNED), locked_until, actions_forced_off FROM users
+WHERE locked_until IS NOT NULL OR actions_forced_off != 0;
+
+ALTER TABLE users
+DROP COLUMN locked_until,
+DROP COLUMN actions_forced_off;
@@ -1,107 +1,483 @@
-use crate::{utils, Context, Error};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::commands::quote;
+use crate::handlers::action_limits::{Limit, RateLimited};
+use crate::handlers::badges::check_badges;
+use crate::structs::action::{ActionBan, ActionDefinition};
+use crate::structs::pronouns::Pronouns;
+use crate::{Context, Data, Error};
 use poise::CreateReply;
-use serenity::all::{CreateAllowedMentions, CreateAttachment, Message};
-use serenity::model::prelude::UserId;
-use serenity::prelude::Mentionable;
+use serenity::all::{
+    ButtonStyle, ChannelId, Colour, ComponentInteractionCollector, CreateActionRow,
+    CreateAllowedMentions, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, Mentionable, Message, User, UserId,
+};
+use time::OffsetDateTime;
+use tracing::{error, warn};
+
+/// Discord allows 5 message context menu commands, one of which is used for quoting
+const MAX_ACTION_CONTEXT_MENUS: usize = 4;
+/// Discord allows 5 user context menu commands, one of which is used for viewing profiles
+const MAX_ACTION_USER_CONTEXT_MENUS: usize = 4;
+/// Discord allows 25 subcommands per command, stats and leaderboard included
+const MAX_SUBCOMMANDS: usize = 25;
+/// How many members to show on action leaderboards
+const LEADERBOARD_LIMIT: u32 = 10;
+
+/// Check that the invoker accepts the action themselves, explaining privately if not. Members
+/// can't use actions they don't want to receive
+async fn check_invoker_consent(ctx: Context<'_>, action: &ActionDefinition) -> Result<bool, Error> {
+    let db = &ctx.data().database_controller;
+    let invoker = db.get_user_by_discord_id(ctx.author().id.into()).await?;
+    let forced_off = db
+        .moderation_get(ctx.author().id.into())
+        .await?
+        .is_some_and(|moderation| moderation.actions_forced_off);
+    let consents = !forced_off
+        && invoker
+            .as_ref()
+            .map_or(ctx.data().config.actions.allowed_by_default, |profile| {
+                profile.accepts_action_category(&action.category)
+            });
+
+    if !consents {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    ":x: You aren't accepting {} actions yourself, so you can't use them on others either. Turn them on with `/profiles edit` and `/profiles action-category`",
+                    action.category
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+    }
+
+    Ok(consents)
+}
+
+/// Check whether a target accepts the action, returning their pronouns if they do.
+///
+/// Members without a profile haven't said either way, so they get the configured default. The
+/// target of an action that asks first gets to decide when asked instead, unless an admin turned
+/// actions off for them. Callers never tell the invoker why the target isn't accepting actions,
+/// as whether they opted out or had actions turned off by an admin is their own business.
+async fn target_consent(
+    ctx: Context<'_>,
+    action: &ActionDefinition,
+    target: &User,
+) -> Result<Option<Pronouns>, Error> {
+    let db = &ctx.data().database_controller;
+    let profile = db.get_user_by_discord_id(target.id.into()).await?;
+    let forced_off = db
+        .moderation_get(target.id.into())
+        .await?
+        .is_some_and(|moderation| moderation.actions_forced_off);
 
-async fn check_if_allowed(ctx: Context<'_>, message: Message) -> Result<bool, Error> {
-    let user = ctx
+    let consents = !forced_off
+        && ((action.ask_first && target.id != ctx.author().id)
+            || profile
+                .as_ref()
+                .map_or(ctx.data().config.actions.allowed_by_default, |profile| {
+                    profile.accepts_action_category(&action.category)
+                }));
+
+    Ok(consents
+        .then(|| Pronouns::from_profile(profile.and_then(|profile| profile.pronouns).as_deref())))
+}
+
+/// Check that the invoker isn't banned from using actions, telling them privately if they are
+async fn check_ban(ctx: Context<'_>) -> Result<bool, Error> {
+    let ban = ctx
         .data()
         .database_controller
-        .get_user_by_discord_id(message.author.id.into())
+        .action_ban_get(ctx.author().id.into())
+        .await?;
+
+    if let Some(ban) = ban {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    ":no_entry: You're banned from using actions until <t:{}:f> ({})",
+                    ban.banned_until.unix_timestamp(),
+                    ban.reason
+                ))
+                .ephemeral(true),
+        )
         .await?;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
 
-    if user.is_none() {
+/// Why a target was left out because of a rate limit
+fn limit_reason(limited: RateLimited, target: &User) -> String {
+    let retry_at = (OffsetDateTime::now_utc() + limited.retry_after).unix_timestamp() + 1;
+    match limited.limit {
+        Limit::Actor => format!(
+            ":hourglass: Slow down! You can use another action <t:{}:R>",
+            retry_at
+        ),
+        Limit::Target => format!(
+            ":hourglass: {} just received an action, try again <t:{}:R>",
+            target.name, retry_at
+        ),
+        Limit::Pair => format!(
+            ":hourglass: You've used a lot of actions on {} lately, try again <t:{}:R>",
+            target.name, retry_at
+        ),
+    }
+}
+
+/// Check the action rate limits for every target, counting this use towards them for the targets
+/// that pass unless `record` is false, in which case call `record_uses` once the action goes
+/// ahead. Targets that hit a limit are left out, with the reason added to `skipped`. Returns
+/// `None` when the invoker can't use actions at all right now, which was explained to them
+/// privately. Members who keep running into the limits are banned from actions for a while
+async fn check_rate_limits<'a>(
+    ctx: Context<'_>,
+    targets: Vec<(&'a User, Pronouns)>,
+    record: bool,
+    skipped: &mut Vec<String>,
+) -> Result<Option<Vec<(&'a User, Pronouns)>>, Error> {
+    let config = &ctx.data().config.actions.rate_limits;
+    let actor = ctx.author().id;
+
+    let mut passed = Vec::new();
+    let mut actor_limited = None;
+    let banned = {
+        let mut limiter = ctx.data().action_limiter.lock().await;
+        let mut any_limited = false;
+
+        for (target, pronouns) in targets {
+            match limiter.check(config, actor, target.id) {
+                Ok(()) => passed.push((target, pronouns)),
+                // The invoker's own cooldown applies to every target alike
+                Err(limited) if limited.limit == Limit::Actor => {
+                    any_limited = true;
+                    actor_limited = Some(limit_reason(limited, target));
+                    break;
+                }
+                Err(limited) => {
+                    any_limited = true;
+                    skipped.push(limit_reason(limited, target));
+                }
+            }
+        }
+
+        if record && actor_limited.is_none() {
+            for (target, _) in &passed {
+                limiter.record(config, actor, target.id);
+            }
+        }
+
+        any_limited && limiter.record_violation(config, actor)
+    };
+
+    if banned {
+        let banned_until =
+            OffsetDateTime::now_utc() + Duration::from_secs(config.ban_duration_secs);
         ctx.data()
             .database_controller
-            .create_user(message.author.id.into())
+            .action_ban_set(ActionBan {
+                user_id: actor.get() as i64,
+                banned_until,
+                reason: "Spamming actions".to_string(),
+            })
             .await?;
-    }
 
-    let user = ctx
-        .data()
-        .database_controller
-        .get_user_by_discord_id(message.author.id.into())
+        warn!("Banned {} from actions for spamming them", actor);
+
+        if let Err(e) = ChannelId::new(ctx.data().config.channels.logs_mod)
+            .send_message(
+                ctx.serenity_context(),
+                CreateMessage::new().content(format!(
+                    ":shield: {} was automatically banned from actions until <t:{}:f> for spamming them",
+                    actor.mention(),
+                    banned_until.unix_timestamp()
+                )),
+            )
+            .await
+        {
+            warn!("Couldn't log an action ban: {:?}", e);
+        }
+
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    ":no_entry: You've been using actions too quickly, so you're banned from them until <t:{}:f>",
+                    banned_until.unix_timestamp()
+                ))
+                .ephemeral(true),
+        )
         .await?;
+        return Ok(None);
+    }
+
+    if let Some(reason) = actor_limited {
+        ctx.send(CreateReply::default().content(reason).ephemeral(true))
+            .await?;
+        return Ok(None);
+    }
+
+    Ok(Some(passed))
+}
+
+/// Count a use of an action towards the rate limits, for actions whose limits were checked
+/// without recording the use
+async fn record_uses(ctx: Context<'_>, targets: &[(&User, Pronouns)]) {
+    let config = &ctx.data().config.actions.rate_limits;
+    let mut limiter = ctx.data().action_limiter.lock().await;
+    for (target, _) in targets {
+        limiter.record(config, ctx.author().id, target.id);
+    }
+}
 
-    Ok(user.unwrap().actions_allowed)
+/// The action a generated command performs
+fn command_action(ctx: Context<'_>) -> Result<&ActionDefinition, Error> {
+    ctx.command()
+        .custom_data
+        .downcast_ref::<ActionDefinition>()
+        .ok_or_else(|| format!("Command {} has no action", ctx.command().name).into())
 }
 
-#[poise::command(context_menu_command = "Hug User")]
-pub async fn use_action_hug(
+/// Ask the target of an action whether they want it, returning whether they accepted. The
+/// request is posted publicly so the target gets pinged, and is updated with their answer
+async fn ask_target(
     ctx: Context<'_>,
-    #[description = "The target message to use the action with"] message: Message,
-) -> Result<(), Error> {
-    ctx.defer().await?;
+    action: &ActionDefinition,
+    target: &User,
+) -> Result<bool, Error> {
+    // Discord only accepts responses to an interaction for 15 minutes
+    let timeout = Duration::from_secs(ctx.data().config.actions.request_timeout_secs)
+        .min(Duration::from_secs(14 * 60));
+    let expires_at = OffsetDateTime::now_utc() + timeout;
+
+    let ctx_id = ctx.id();
+    let accept_button_id = format!("{}accept", ctx_id);
+    let decline_button_id = format!("{}decline", ctx_id);
 
-    let allowed = check_if_allowed(ctx, message.clone()).await?;
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(&accept_button_id)
+            .style(ButtonStyle::Success)
+            .label("Accept"),
+        CreateButton::new(&decline_button_id)
+            .style(ButtonStyle::Secondary)
+            .label("Decline"),
+    ]);
 
-    if !allowed {
-        ctx.send(CreateReply::default().content(
-            ":x: Sorry, either that user has disabled actions or has no profile to check against",
-        ).ephemeral(true))
+    let request = ctx
+        .send(
+            CreateReply::default()
+                .content(format!(
+                    "{}, {} wants to {} you! This request expires <t:{}:R>",
+                    target.mention(),
+                    ctx.author().mention(),
+                    action.name,
+                    expires_at.unix_timestamp()
+                ))
+                .components(vec![buttons])
+                .allowed_mentions(CreateAllowedMentions::new().users(vec![target.id])),
+        )
         .await?;
-        return Ok(());
-    }
 
-    let action_img = utils::get_random_action_image("hug".to_string()).await;
+    // Wait for the target to press one of the buttons
+    let press = ComponentInteractionCollector::new(ctx)
+        .author_id(target.id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(timeout)
+        .await;
 
-    // Match the result of the action image
-    match action_img {
-        Ok(img) => {
-            let user = UserId::new(ctx.author().id.into());
-            let target = UserId::new(message.clone().author.id.into());
-            let builder = CreateReply::default()
-                .content(format!("{} hugs {}", user.mention(), target.mention()))
-                .attachment(CreateAttachment::url(ctx.http(), &img).await?)
-                .allowed_mentions(CreateAllowedMentions::default().users(vec![user, target]));
+    let accepted = press
+        .as_ref()
+        .is_some_and(|press| press.data.custom_id == accept_button_id);
+    let answer = match (&press, accepted) {
+        (Some(_), true) => format!(
+            ":white_check_mark: {} accepted {}'s {}",
+            target.mention(),
+            ctx.author().mention(),
+            action.name
+        ),
+        (Some(_), false) => format!(
+            ":x: {} declined {}'s {}",
+            target.mention(),
+            ctx.author().mention(),
+            action.name
+        ),
+        (None, _) => format!(
+            ":hourglass: {}'s {} for {} went unanswered",
+            ctx.author().mention(),
+            action.name,
+            target.mention()
+        ),
+    };
 
-            ctx.send(builder).await?;
+    match press {
+        Some(press) => {
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    CreateInteractionResponse::UpdateMessage(
+                        CreateInteractionResponseMessage::new()
+                            .content(answer)
+                            .components(vec![]),
+                    ),
+                )
+                .await?;
         }
-        Err(_) => {
-            ctx.send(
-                CreateReply::default()
-                    .content(":x: Something went wrong while fetching the action image")
-                    .ephemeral(true),
-            )
-            .await?;
+        None => {
+            request
+                .edit(
+                    ctx,
+                    CreateReply::default().content(answer).components(vec![]),
+                )
+                .await?;
         }
     }
 
-    Ok(())
+    Ok(accepted)
 }
 
-#[poise::command(context_menu_command = "Kiss User")]
-pub async fn use_action_kiss(
+/// Use an action on one or more members, posting a GIF of it. Every way of using an action ends
+/// up here. Targets who don't accept the action or hit a rate limit are left out, and listed
+/// privately to the invoker
+async fn perform_action(
     ctx: Context<'_>,
-    #[description = "The target message to use the action with"] message: Message,
+    action: &ActionDefinition,
+    targets: &[User],
+    note: Option<&str>,
 ) -> Result<(), Error> {
-    ctx.defer().await?;
-    let allowed = check_if_allowed(ctx, message.clone()).await?;
+    if !check_ban(ctx).await? || !check_invoker_consent(ctx, action).await? {
+        return Ok(());
+    }
+
+    let mut unique_targets: Vec<&User> = Vec::new();
+    for target in targets {
+        if !unique_targets.iter().any(|unique| unique.id == target.id) {
+            unique_targets.push(target);
+        }
+    }
+
+    if action.ask_first && unique_targets.len() > 1 {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    ":x: {} asks for permission first, so it can only be used on one person at a time",
+                    action.label()
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut skipped = Vec::new();
+    let mut consenting = Vec::new();
+    for target in unique_targets {
+        match target_consent(ctx, action, target).await? {
+            Some(pronouns) => consenting.push((target, pronouns)),
+            None => skipped.push(format!(
+                ":x: Members choose which actions they receive, and {} isn't accepting this one right now",
+                target.name
+            )),
+        }
+    }
+
+    let targets = if consenting.is_empty() {
+        consenting
+    } else {
+        // Asking can be turned down, which shouldn't count as a use
+        match check_rate_limits(ctx, consenting, !action.ask_first, &mut skipped).await? {
+            Some(targets) => targets,
+            None => return Ok(()),
+        }
+    };
 
-    if !allowed {
-        ctx.send(CreateReply::default().content(
-            ":x: Sorry, either that user has disabled actions or has no profile to check against",
-        ).ephemeral(true))
+    if targets.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content(skipped.join("\n"))
+                .ephemeral(true),
+        )
         .await?;
         return Ok(());
     }
 
-    let action_img = utils::get_random_action_image("kiss".to_string()).await;
+    if action.ask_first {
+        let (target, _) = targets[0];
+        if target.id != ctx.author().id && !ask_target(ctx, action, target).await? {
+            return Ok(());
+        }
+        record_uses(ctx, &targets).await;
+    }
+
+    // Only defer once we know the reply will be public, the consent explanation is private
+    ctx.defer().await?;
+
+    let action_img = ctx.data().image_providers.random_image(action).await;
 
     // Match the result of the action image
     match action_img {
         Ok(img) => {
-            let user = UserId::new(ctx.author().id.into());
-            let target = UserId::new(message.author.id.into());
+            let user = ctx.author().id;
+            let db = &ctx.data().database_controller;
+            let target_ids: Vec<UserId> = targets.iter().map(|(target, _)| target.id).collect();
+
+            let content = match targets.as_slice() {
+                [(target, pronouns)] => {
+                    // Count before recording this use, so count it in by hand
+                    let received = db
+                        .action_count_received_by_action(target.id.into(), &action.name)
+                        .await?
+                        + 1;
+                    let from_user = db
+                        .action_count_between(user.into(), target.id.into(), &action.name)
+                        .await?
+                        + 1;
+
+                    format!(
+                        "{}\n{}",
+                        action.render(user, &target_ids, pronouns, note),
+                        action.render_stats(user, target.id, received, from_user)
+                    )
+                }
+                // There's no telling which pronouns to use for a group
+                _ => action.render(user, &target_ids, &Pronouns::they(), note),
+            };
+
+            // Only ping the members who accepted the action
+            let mut mentioned = vec![user];
+            mentioned.extend(&target_ids);
+
             let builder = CreateReply::default()
-                .content(format!("{} kisses {}", user.mention(), target.mention()))
-                .attachment(CreateAttachment::url(ctx.http(), &img).await?)
-                .allowed_mentions(CreateAllowedMentions::default().users(vec![user, target]));
+                .content(content)
+                .attachment(CreateAttachment::bytes(img.data, img.filename))
+                .allowed_mentions(CreateAllowedMentions::default().users(mentioned));
 
             ctx.send(builder).await?;
+
+            for target_id in &target_ids {
+                db.action_event_create(user.into(), target_id.get(), &action.name)
+                    .await?;
+            }
+
+            check_badges(ctx.serenity_context(), ctx.data(), user).await;
+
+            if !skipped.is_empty() {
+                ctx.send(
+                    CreateReply::default()
+                        .content(format!(
+                            "Some members were left out:\n{}",
+                            skipped.join("\n")
+                        ))
+                        .ephemeral(true),
+                )
+                .await?;
+            }
         }
-        Err(_) => {
+        Err(e) => {
+            error!("Couldn't get an image for action {}: {}", action.name, e);
             ctx.send(
                 CreateReply::default()
                     .content(":x: Something went wrong while fetching the action image")
@@ -114,45 +490,258 @@ pub async fn use_action_kiss(
     Ok(())
 }
 
-#[poise::command(context_menu_command = "Pat User")]
-pub async fn use_action_pat(
+/// Use an action on someone. The subcommands are added by `action_commands`
+#[poise::command(slash_command)]
+async fn action(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Template for the `/action <name>` subcommands, see `action_commands`
+#[poise::command(slash_command)]
+async fn slash_action(
+    ctx: Context<'_>,
+    #[description = "Who to use the action on"] user: User,
+    #[description = "Something to say along with it"]
+    #[max_length = 500]
+    message: Option<String>,
+    #[description = "Someone else to include"] user2: Option<User>,
+    #[description = "Someone else to include"] user3: Option<User>,
+    #[description = "Someone else to include"] user4: Option<User>,
+    #[description = "Someone else to include"] user5: Option<User>,
+) -> Result<(), Error> {
+    let targets: Vec<User> = [Some(user), user2, user3, user4, user5]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    perform_action(ctx, command_action(ctx)?, &targets, message.as_deref()).await
+}
+
+/// Template for the message context menu entries, see `action_commands`
+#[poise::command(context_menu_command = "Action")]
+async fn message_action(
     ctx: Context<'_>,
     #[description = "The target message to use the action with"] message: Message,
 ) -> Result<(), Error> {
-    ctx.defer().await?;
-    let allowed = check_if_allowed(ctx, message.clone()).await?;
+    perform_action(ctx, command_action(ctx)?, &[message.author], None).await
+}
 
-    if !allowed {
-        ctx.send(CreateReply::default().content(
-            ":x: Sorry, either that user has disabled actions or has no profile to check against",
-        ).ephemeral(true))
-        .await?;
-        return Ok(());
+/// Template for the user context menu entries, see `action_commands`
+#[poise::command(context_menu_command = "Action")]
+async fn user_action(
+    ctx: Context<'_>,
+    #[description = "The user to use the action on"] user: User,
+) -> Result<(), Error> {
+    perform_action(ctx, command_action(ctx)?, &[user], None).await
+}
+
+/// Show how many actions someone gave and received
+#[poise::command(slash_command)]
+async fn stats(
+    ctx: Context<'_>,
+    #[description = "Whose stats to show, yourself if not given"] user: Option<User>,
+) -> Result<(), Error> {
+    let user = user.as_ref().unwrap_or_else(|| ctx.author());
+    let db = &ctx.data().database_controller;
+
+    let given = db.action_counts_given(user.id.into()).await?;
+    let received = db.action_counts_received(user.id.into()).await?;
+
+    // Combine both into (given, received) per action, in alphabetical order
+    let mut per_action: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for (action, count) in given {
+        per_action.entry(action).or_default().0 = count;
+    }
+    for (action, count) in received {
+        per_action.entry(action).or_default().1 = count;
     }
 
-    let action_img = utils::get_random_action_image("pat".to_string()).await;
+    let total_given: i64 = per_action.values().map(|(given, _)| given).sum();
+    let total_received: i64 = per_action.values().map(|(_, received)| received).sum();
 
-    // Match the result of the action image
-    match action_img {
-        Ok(img) => {
-            let user = UserId::new(ctx.author().id.into());
-            let target = UserId::new(message.author.id.into());
-            let builder = CreateReply::default()
-                .content(format!("{} pats {}", user.mention(), target.mention()))
-                .attachment(CreateAttachment::url(ctx.http(), &img).await?)
-                .allowed_mentions(CreateAllowedMentions::default().users(vec![user, target]));
+    let breakdown = if per_action.is_empty() {
+        "No actions yet".to_string()
+    } else {
+        per_action
+            .iter()
+            .map(|(action, (given, received))| {
+                format!("**{}** - {} given, {} received", action, given, received)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
 
-            ctx.send(builder).await?;
+    let embed = CreateEmbed::default()
+        .title(format!(":bar_chart: Action stats of {}", user.name))
+        .thumbnail(user.face())
+        .field("Given", total_given.to_string(), true)
+        .field("Received", total_received.to_string(), true)
+        .field("By action", breakdown, false)
+        .color(Colour::FABLED_PINK);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Show who gives and receives the most actions
+#[poise::command(slash_command)]
+async fn leaderboard(
+    ctx: Context<'_>,
+    #[description = "Only count one action, e.g. hug"]
+    #[autocomplete = "autocomplete_action"]
+    action: Option<String>,
+) -> Result<(), Error> {
+    let action = action.map(|action| action.trim().to_lowercase());
+    let db = &ctx.data().database_controller;
+
+    let top_actors = db
+        .action_top_actors(action.as_deref(), LEADERBOARD_LIMIT)
+        .await?;
+    let top_targets = db
+        .action_top_targets(action.as_deref(), LEADERBOARD_LIMIT)
+        .await?;
+
+    let title = match &action {
+        Some(action) => format!(":trophy: {} leaderboard", action),
+        None => ":trophy: Action leaderboard".to_string(),
+    };
+
+    let embed = CreateEmbed::default()
+        .title(title)
+        .field("Most given", quote::leaderboard(&top_actors), true)
+        .field("Most received", quote::leaderboard(&top_targets), true)
+        .color(Colour::FABLED_PINK);
+
+    ctx.send(
+        CreateReply::default()
+            .embed(embed)
+            .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Suggest the configured actions
+async fn autocomplete_action(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    ctx.data()
+        .config
+        .actions
+        .list
+        .iter()
+        .map(|action| action.name.clone())
+        .filter(|name| name.contains(&partial.to_lowercase()))
+        .collect()
+}
+
+/// Whether a name can be used as a slash command name
+fn is_valid_command_name(name: &str) -> bool {
+    (1..=32).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Generate the commands for the configured actions: `/action <name>` for every action, and
+/// message and user context menu entries for those that ask for them. Actions that can't be registered are
+/// skipped with a warning rather than stopping the bot
+pub fn action_commands(actions: &[ActionDefinition]) -> Vec<poise::Command<Data, Error>> {
+    let mut parent = action();
+    parent.subcommand_required = true;
+    parent.subcommands = vec![stats(), leaderboard()];
+    let mut context_menus = Vec::new();
+    let mut user_context_menus = Vec::new();
+
+    for definition in actions {
+        if !is_valid_command_name(&definition.name) {
+            warn!(
+                "Skipping action {:?}, names must be 1-32 lowercase letters, digits, - or _",
+                definition.name
+            );
+            continue;
         }
-        Err(_) => {
-            ctx.send(
-                CreateReply::default()
-                    .content(":x: Something went wrong while fetching the action image")
-                    .ephemeral(true),
-            )
-            .await?;
+        if parent
+            .subcommands
+            .iter()
+            .any(|command| command.name == definition.name)
+        {
+            warn!(
+                "Skipping action {:?}, the name is already taken",
+                definition.name
+            );
+            continue;
+        }
+        if parent.subcommands.len() >= MAX_SUBCOMMANDS {
+            warn!(
+                "Skipping action {:?}, Discord allows at most {} subcommands of /action",
+                definition.name, MAX_SUBCOMMANDS
+            );
+            continue;
+        }
+
+        let mut command = slash_action();
+        command.name = definition.name.clone();
+        command.identifying_name = format!("action_{}", definition.name);
+        command.description = Some(format!("{} someone", definition.label()));
+        command.nsfw_only = definition.nsfw;
+        command.custom_data = Box::new(definition.clone());
+        parent.subcommands.push(command);
+
+        if definition.context_menu {
+            if context_menus.len() < MAX_ACTION_CONTEXT_MENUS {
+                let mut command = message_action();
+                command.name = format!("{}_message", definition.name);
+                command.identifying_name = format!("action_{}_message", definition.name);
+                command.context_menu_name = Some(format!("{} User", definition.label()));
+                command.nsfw_only = definition.nsfw;
+                command.custom_data = Box::new(definition.clone());
+                context_menus.push(command);
+            } else {
+                warn!(
+                    "Not adding a message context menu entry for action {:?}, Discord allows at most {} for actions",
+                    definition.name, MAX_ACTION_CONTEXT_MENUS
+                );
+            }
+        }
+
+        if definition.user_context_menu {
+            if user_context_menus.len() < MAX_ACTION_USER_CONTEXT_MENUS {
+                let mut command = user_action();
+                command.name = format!("{}_user", definition.name);
+                command.identifying_name = format!("action_{}_user", definition.name);
+                command.context_menu_name = Some(definition.label());
+                command.nsfw_only = definition.nsfw;
+                command.custom_data = Box::new(definition.clone());
+                user_context_menus.push(command);
+            } else {
+                warn!(
+                    "Not adding a user context menu entry for action {:?}, Discord allows at most {} for actions",
+                    definition.name, MAX_ACTION_USER_CONTEXT_MENUS
+                );
+            }
         }
     }
 
-    Ok(())
+    let mut commands = vec![parent];
+    commands.extend(context_menus);
+    commands.extend(user_context_menus);
+    commands
+}
+
+/// Suggest the configured action categories
+pub async fn autocomplete_category(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let mut categories: Vec<String> = ctx
+        .data()
+        .config
+        .actions
+        .list
+        .iter()
+        .map(|action| action.category.clone())
+        .filter(|category| category.contains(&partial.to_lowercase()))
+        .collect();
+    categories.sort();
+    categories.dedup();
+
+    categories
 }
@@ -33,8 +33,8 @@ pub async fn eval(
         });
 
         // If the user has already run an eval command, delete the message
-        if last_eval.is_some() {
-            let _ = last_eval.unwrap().delete(&ctx).await;
+        if let Some(last_eval) = last_eval {
+            let _ = last_eval.delete(&ctx).await;
             info!(
                 "User {} tried to run eval without permission, and I deleted the message",
                 ctx.author().id,
@@ -1,12 +1,29 @@
-use crate::structs::user::User as UserStruct;
+use std::time::Duration;
+
+use crate::handlers::badges::get_badge;
+use crate::structs::user::{ProfileModeration, QuotePermission, User as UserStruct};
+use crate::utils::parse_duration;
 use crate::{Context, Error};
-use poise::CreateReply;
-use serenity::all::{Colour, CreateEmbed, User};
+use poise::{ChoiceParameter, CreateReply};
+use serde_json::json;
+use serenity::all::{
+    ButtonStyle, ChannelId, Colour, ComponentInteractionCollector, CreateActionRow,
+    CreateAllowedMentions, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, GuildId, Mentionable, User,
+};
+use time::OffsetDateTime;
 
 /// Commands related to profiles in the bot
 #[poise::command(
     slash_command,
-    subcommands("view", "edit"),
+    subcommands(
+        "view",
+        "edit",
+        "action_category",
+        "export",
+        "delete_my_data",
+        "moderate"
+    ),
     check = "ensure_profile_is_setup"
 )]
 pub async fn profiles(_ctx: Context<'_>) -> Result<(), Error> {
@@ -25,55 +42,186 @@ async fn ensure_profile_is_setup(ctx: Context<'_>) -> Result<bool, Error> {
     if profile.is_none() {
         ctx.data()
             .database_controller
-            .create_user(ctx.author().id.into())
+            .create_user(
+                ctx.author().id.into(),
+                ctx.data().config.actions.allowed_by_default,
+            )
             .await?;
     }
 
     Ok(true)
 }
 
+/// Get a user's profile, creating one if it doesn't exist
+async fn get_or_create_profile(ctx: Context<'_>, discord_id: u64) -> Result<UserStruct, Error> {
+    let profile = ctx
+        .data()
+        .database_controller
+        .get_user_by_discord_id(discord_id)
+        .await?;
+
+    match profile {
+        Some(profile) => Ok(profile),
+        None => Ok(ctx
+            .data()
+            .database_controller
+            .create_user(discord_id, ctx.data().config.actions.allowed_by_default)
+            .await?),
+    }
+}
+
 /// View a user's profile
 #[poise::command(slash_command)]
 pub async fn view(
-    _ctx: Context<'_>,
+    ctx: Context<'_>,
     #[description = "The user to view the profile of, defaults to yourself"] user: Option<User>,
 ) -> Result<(), Error> {
     // Get the profile of the provided user, or the user who ran the command
-    let target_user = user.unwrap_or_else(|| _ctx.author().clone());
+    let target_user = user.unwrap_or_else(|| ctx.author().clone());
 
-    // Get the profile of the target user
-    let profile = _ctx
-        .data()
-        .database_controller
-        .get_user_by_discord_id(target_user.id.into())
-        .await?;
+    send_profile(ctx, target_user).await
+}
 
-    match profile {
-        Some(profile) => {
-            let profile_embed = CreateEmbed::default()
-                .title(format!("Profile of {}", target_user.tag()))
-                .description(format!(
-                    "About: {}\nPronouns: {}\nActions Allowed: {}",
-                    profile.about.unwrap_or("No about section".to_string()),
-                    profile.pronouns.unwrap_or("No pronouns set".to_string()),
-                    if profile.actions_allowed { "Yes" } else { "No" }
-                ))
-                .color(Colour::FABLED_PINK);
+/// View a user's profile from the user context menu
+#[poise::command(
+    context_menu_command = "View Profile",
+    check = "ensure_profile_is_setup"
+)]
+pub async fn view_profile(
+    ctx: Context<'_>,
+    #[description = "The user to view the profile of"] user: User,
+) -> Result<(), Error> {
+    send_profile(ctx, user).await
+}
 
-            _ctx.send(CreateReply::default().embed(profile_embed))
-                .await?;
-        }
+/// Build and send the profile embed for the target user
+async fn send_profile(ctx: Context<'_>, target_user: User) -> Result<(), Error> {
+    let db = &ctx.data().database_controller;
+
+    // Get the profile of the target user
+    let profile = match db.get_user_by_discord_id(target_user.id.into()).await? {
+        Some(profile) => profile,
         None => {
-            // Send a emphul messagrespond(formate if the user doesn't have a profile
-            _ctx.send(
+            // Send an ephemeral message if the user doesn't have a profile
+            ctx.send(
                 CreateReply::default()
                     .content(":x: User has no profile")
                     .ephemeral(true),
             )
             .await?;
+            return Ok(());
         }
+    };
+
+    let vouch = db.vouch_get_by_user_id(target_user.id.into()).await?;
+    let quote_count = db.quote_count_by_user_id(target_user.id.into()).await?;
+    let actions_given = db.action_count_given(target_user.id.into()).await?;
+    let actions_received = db.action_count_received(target_user.id.into()).await?;
+    let badges = db.badge_get_by_user_id(target_user.id.into()).await?;
+
+    // The accent colour is only sent when fetching the user directly, so ask the API for it
+    let accent_colour = ctx
+        .http()
+        .get_user(target_user.id)
+        .await
+        .ok()
+        .and_then(|user| user.accent_colour)
+        .unwrap_or(Colour::FABLED_PINK);
+
+    // The member may have left the guild, in which case we simply don't show a join date. The
+    // profile can be viewed from anywhere, so always look in the main guild
+    let joined_at = GuildId::new(ctx.data().config.main_guild_id)
+        .member(ctx.serenity_context(), target_user.id)
+        .await
+        .ok()
+        .and_then(|member| member.joined_at);
+
+    let vouch_info = match vouch {
+        Some(vouch) => format!(
+            "Vouched by <@{}> on <t:{}:f>\nApproved by <@{}> on <t:{}:f>",
+            vouch.vouched_by,
+            vouch.vouched_at.unix_timestamp(),
+            vouch.approved_by,
+            vouch.approved_at.unix_timestamp()
+        ),
+        None => "No vouch on record".to_string(),
+    };
+
+    let actions_forced_off = db
+        .moderation_get(target_user.id.into())
+        .await?
+        .is_some_and(|moderation| moderation.actions_forced_off);
+    let actions_allowed = if profile.actions_allowed && !actions_forced_off {
+        "Yes"
+    } else {
+        "No"
+    };
+    let blocked_categories = if profile.blocked_action_categories.is_empty() {
+        "None".to_string()
+    } else {
+        profile.blocked_action_categories.join(", ")
+    };
+
+    let mut profile_embed = CreateEmbed::default()
+        .title(format!("Profile of {}", target_user.tag()))
+        .thumbnail(target_user.face())
+        .description(
+            profile
+                .about
+                .unwrap_or_else(|| "No about section".to_string()),
+        )
+        .field(
+            "Pronouns",
+            profile
+                .pronouns
+                .unwrap_or_else(|| "No pronouns set".to_string()),
+            true,
+        )
+        .field("Quotes Allowed", profile.quotes_allowed.name(), true);
+
+    // Which actions someone accepts is between them and the bot, so only show it to them
+    if ctx.author().id == target_user.id {
+        profile_embed = profile_embed
+            .field("Actions Allowed", actions_allowed, true)
+            .field("Blocked Action Categories", blocked_categories, true);
     }
 
+    let profile_embed = profile_embed
+        .field(
+            "Joined",
+            match joined_at {
+                Some(joined_at) => format!("<t:{}:f>", joined_at.unix_timestamp()),
+                None => "Not in the server".to_string(),
+            },
+            true,
+        )
+        .field("Vouch", vouch_info, false)
+        .field("Quotes", quote_count.to_string(), true)
+        .field("Actions Given", actions_given.to_string(), true)
+        .field("Actions Received", actions_received.to_string(), true)
+        .field(
+            "Badges",
+            if badges.is_empty() {
+                "No badges yet".to_string()
+            } else {
+                badges
+                    .iter()
+                    .filter_map(|earned| get_badge(&earned.badge_id))
+                    .map(|badge| format!("{} {}", badge.emoji, badge.name))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+            false,
+        )
+        .color(accent_colour);
+
+    ctx.send(
+        CreateReply::default()
+            .embed(profile_embed)
+            .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+    )
+    .await?;
+
     Ok(())
 }
 
@@ -85,9 +233,14 @@ pub async fn edit(
     #[description = "Your pronouns"] pronouns: Option<String>,
     #[description = "Whether you want to allow actions to be performed on you"]
     actions_allowed: Option<bool>,
+    #[description = "Who is allowed to quote you"] quotes_allowed: Option<QuotePermission>,
 ) -> Result<(), Error> {
     // If no options are provided, send a help message
-    if about.is_none() && pronouns.is_none() && actions_allowed.is_none() {
+    if about.is_none()
+        && pronouns.is_none()
+        && actions_allowed.is_none()
+        && quotes_allowed.is_none()
+    {
         _ctx.send(
             CreateReply::default()
                 .content("Please provide at least one option to edit")
@@ -97,33 +250,42 @@ pub async fn edit(
         return Ok(());
     }
 
-    // Get the profile of the user who ran the command
-    let profile = _ctx
+    let moderation = _ctx
         .data()
         .database_controller
-        .get_user_by_discord_id(_ctx.author().id.into())
+        .moderation_get(_ctx.author().id.into())
+        .await?
+        .unwrap_or_default();
+
+    // Admins can lock the about section and pronouns from edits. Which actions and quotes a
+    // member accepts is always theirs to change
+    if let Some(locked_until) = moderation
+        .locked_until
+        .filter(|_| moderation.is_locked() && (about.is_some() || pronouns.is_some()))
+    {
+        _ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    ":lock: Your about section and pronouns have been locked from edits by an admin until <t:{}:f>, you can still change the other settings",
+                    locked_until.unix_timestamp()
+                ))
+                .ephemeral(true),
+        )
         .await?;
+        return Ok(());
+    }
 
-    // If the user doesn't have a profile, create one
-    let profile = match profile {
-        Some(profile) => profile,
-        None => {
-            _ctx.data()
-                .database_controller
-                .create_user(_ctx.author().id.into())
-                .await?
-        }
-    };
+    // Get the profile of the user who ran the command, creating one if it doesn't exist
+    let profile = get_or_create_profile(_ctx, _ctx.author().id.into()).await?;
 
-    // Generate a user struct with the updated values
+    // Generate a user struct with the updated values, keeping anything that wasn't provided
     let updated_profile = UserStruct {
-        id: profile.id,
-        discord_id: profile.discord_id,
-        about,
-        pronouns,
+        about: about.or(profile.about),
+        pronouns: pronouns.or(profile.pronouns),
         actions_allowed: actions_allowed.unwrap_or(profile.actions_allowed),
+        quotes_allowed: quotes_allowed.unwrap_or(profile.quotes_allowed),
+        ..profile
     };
-
     // Update the user's profile
     _ctx.data()
         .database_controller
@@ -133,10 +295,482 @@ pub async fn edit(
     // Send a success message
     _ctx.send(
         CreateReply::default()
-            .content(":white_check_mark: Profile updated successfully!")
+            .content(if moderation.actions_forced_off && actions_allowed == Some(true) {
+                ":white_check_mark: Profile updated successfully! Note that actions have been turned off for you by an admin, so they will stay disabled for now."
+            } else {
+                ":white_check_mark: Profile updated successfully!"
+            })
             .ephemeral(true),
     )
     .await?;
 
     Ok(())
 }
+
+/// Turn a category of actions on or off for yourself
+#[poise::command(slash_command, rename = "action-category")]
+pub async fn action_category(
+    ctx: Context<'_>,
+    #[description = "The category of actions, e.g. romantic"]
+    #[autocomplete = "crate::commands::action::autocomplete_category"]
+    category: String,
+    #[description = "Whether you want to receive actions in this category"] allowed: bool,
+) -> Result<(), Error> {
+    let category = category.trim().to_lowercase();
+    if !ctx
+        .data()
+        .config
+        .actions
+        .list
+        .iter()
+        .any(|action| action.category == category)
+    {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(":x: There are no {} actions", category))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut profile = get_or_create_profile(ctx, ctx.author().id.into()).await?;
+
+    profile
+        .blocked_action_categories
+        .retain(|blocked| *blocked != category);
+    if !allowed {
+        profile.blocked_action_categories.push(category.clone());
+    }
+
+    ctx.data().database_controller.update_user(profile).await?;
+
+    ctx.send(
+        CreateReply::default()
+            .content(if allowed {
+                format!(":white_check_mark: You now accept {} actions", category)
+            } else {
+                format!(
+                    ":white_check_mark: You no longer accept {} actions",
+                    category
+                )
+            })
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Export everything the bot stores about you, delivered by DM
+#[poise::command(slash_command)]
+pub async fn export(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let db = &ctx.data().database_controller;
+    let discord_id: u64 = ctx.author().id.into();
+
+    let profile = db.get_user_by_discord_id(discord_id).await?;
+    let quotes_said = db.quote_get_by_user_id(discord_id).await?;
+    let quotes_added = db.quote_get_by_added_by(discord_id).await?;
+    let vouches = db.vouch_get_all_involving(discord_id).await?;
+    let action_events = db.action_event_get_involving(discord_id).await?;
+    let kv_entries = db.kv_get_by_discord_id(discord_id).await?;
+    let badges = db.badge_get_by_user_id(discord_id).await?;
+    let action_ban = db.action_ban_get(discord_id).await?;
+    let quote_votes = db.quote_vote_get_by_user_id(discord_id).await?;
+    let moderation = db.moderation_get(discord_id).await?;
+
+    let quote_json = |quote: &crate::structs::quote::Quote| {
+        json!({
+            "quote_id": quote.quote_id,
+            "user_id": quote.user_id.to_string(),
+            "username": quote.username,
+            "nickname": quote.nickname,
+            "quote": quote.quote,
+            "added_by": quote.added_by.to_string(),
+            "added_at": quote.added_at.unix_timestamp(),
+            "guild_id": quote.guild_id.map(|id| id.to_string()),
+            "channel_id": quote.channel_id.map(|id| id.to_string()),
+            "message_id": quote.message_id.map(|id| id.to_string()),
+            "attachments": quote.attachment_urls(),
+            "reply_username": quote.reply_username,
+            "reply_content": quote.reply_content,
+        })
+    };
+
+    let export = json!({
+        "discord_id": discord_id.to_string(),
+        "exported_at": time::OffsetDateTime::now_utc().unix_timestamp(),
+        "profile": profile.map(|profile| json!({
+            "id": profile.id,
+            "discord_id": profile.discord_id.to_string(),
+            "actions_allowed": profile.actions_allowed,
+            "quotes_allowed": profile.quotes_allowed.name(),
+            "blocked_action_categories": profile.blocked_action_categories,
+            "about": profile.about,
+            "pronouns": profile.pronouns,
+        })),
+        "moderation": moderation.map(|moderation| json!({
+            "locked_until": moderation.locked_until.map(|until| until.unix_timestamp()),
+            "actions_forced_off": moderation.actions_forced_off,
+        })),
+        "quotes_said": quotes_said.iter().map(quote_json).collect::<Vec<_>>(),
+        "quotes_added": quotes_added.iter().map(quote_json).collect::<Vec<_>>(),
+        "quote_votes": quote_votes.iter().map(|(quote_id, voted_at)| json!({
+            "quote_id": quote_id,
+            "voted_at": voted_at.unix_timestamp(),
+        })).collect::<Vec<_>>(),
+        "vouches": vouches.iter().map(|vouch| json!({
+            "user_id": vouch.user_id.to_string(),
+            "vouched_by": vouch.vouched_by.to_string(),
+            "approved_by": vouch.approved_by.to_string(),
+            "vouched_at": vouch.vouched_at.unix_timestamp(),
+            "approved_at": vouch.approved_at.unix_timestamp(),
+        })).collect::<Vec<_>>(),
+        "action_events": action_events.iter().map(|event| json!({
+            "actor_id": event.actor_id.to_string(),
+            "target_id": event.target_id.to_string(),
+            "action": event.action,
+            "created_at": event.created_at.unix_timestamp(),
+        })).collect::<Vec<_>>(),
+        "action_ban": action_ban.map(|ban| json!({
+            "banned_until": ban.banned_until.unix_timestamp(),
+            "reason": ban.reason,
+        })),
+        "badges": badges.iter().map(|badge| json!({
+            "badge_id": badge.badge_id,
+            "earned_at": badge.earned_at.unix_timestamp(),
+        })).collect::<Vec<_>>(),
+        "kv_entries": kv_entries.iter().map(|(key, value)| json!({
+            "key": key,
+            "value": value,
+        })).collect::<Vec<_>>(),
+    });
+
+    let attachment = CreateAttachment::bytes(
+        serde_json::to_vec_pretty(&export)?,
+        format!("sillycord-data-{}.json", discord_id),
+    );
+
+    let dm_result = ctx
+        .author()
+        .direct_message(
+            ctx.serenity_context(),
+            CreateMessage::default()
+                .content(":package: Here's everything sillycord-bot stores about you.")
+                .add_file(attachment),
+        )
+        .await;
+
+    match dm_result {
+        Ok(_) => {
+            ctx.say(":white_check_mark: Your data export has been sent to your DMs!")
+                .await?;
+        }
+        Err(_) => {
+            ctx.say(":x: I couldn't DM you, please enable DMs from server members and try again")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete everything the bot stores about you
+#[poise::command(slash_command, rename = "delete-my-data")]
+pub async fn delete_my_data(ctx: Context<'_>) -> Result<(), Error> {
+    // Define some unique identifiers for the confirmation buttons
+    let ctx_id = ctx.id();
+    let confirm_button_id = format!("{}confirm", ctx_id);
+    let cancel_button_id = format!("{}cancel", ctx_id);
+
+    let components = CreateActionRow::Buttons(vec![
+        CreateButton::new(&confirm_button_id)
+            .style(ButtonStyle::Danger)
+            .label("Delete my data"),
+        CreateButton::new(&cancel_button_id)
+            .style(ButtonStyle::Secondary)
+            .label("Cancel"),
+    ]);
+
+    ctx.send(
+        CreateReply::default()
+            .content(":warning: This will permanently delete your profile, quotes of you, your vouch history and action history. Quotes you added for others are kept but no longer linked to you. This cannot be undone - are you sure?")
+            .components(vec![components])
+            .ephemeral(true),
+    )
+    .await?;
+
+    // Wait for the author to press one of the buttons
+    let press = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(Duration::from_secs(60))
+        .await;
+
+    let press = match press {
+        Some(press) => press,
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content(":x: Timed out, your data was not deleted")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let response = if press.data.custom_id == confirm_button_id {
+        ctx.data()
+            .database_controller
+            .delete_user_data(ctx.author().id.into())
+            .await?;
+
+        ":white_check_mark: All of your data has been deleted."
+    } else {
+        ":x: Cancelled, your data was not deleted"
+    };
+
+    press
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(response)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The profile fields an admin can clear
+#[derive(Debug, poise::ChoiceParameter)]
+pub enum ProfileField {
+    About,
+    Pronouns,
+    All,
+}
+
+/// Admin tools for moderating user profiles
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("clear", "lock", "unlock", "actions"),
+    check = "crate::utils::is_admin"
+)]
+pub async fn moderate(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Send a moderation action to the mod-logs channel
+async fn log_moderation(ctx: Context<'_>, message: String) -> Result<(), Error> {
+    ChannelId::new(ctx.data().config.channels.logs_mod)
+        .send_message(
+            ctx.serenity_context(),
+            CreateMessage::new().content(message),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Clear fields on a user's profile
+#[poise::command(slash_command, guild_only)]
+pub async fn clear(
+    ctx: Context<'_>,
+    #[description = "The user whose profile to clear"] user: User,
+    #[description = "The field to clear"] field: ProfileField,
+    #[description = "The reason for clearing the field"] reason: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let profile = get_or_create_profile(ctx, user.id.into()).await?;
+    let updated_profile = match field {
+        ProfileField::About => UserStruct {
+            about: None,
+            ..profile
+        },
+        ProfileField::Pronouns => UserStruct {
+            pronouns: None,
+            ..profile
+        },
+        ProfileField::All => UserStruct {
+            about: None,
+            pronouns: None,
+            ..profile
+        },
+    };
+
+    ctx.data()
+        .database_controller
+        .update_user(updated_profile)
+        .await?;
+
+    log_moderation(
+        ctx,
+        format!(
+            ":shield: {} cleared {:?} on the profile of {} with a reason of '{}'",
+            ctx.author().mention(),
+            field,
+            user.mention(),
+            reason
+        ),
+    )
+    .await?;
+
+    ctx.say(":white_check_mark: Profile cleared!").await?;
+    Ok(())
+}
+
+/// Lock a user's about section and pronouns from edits for a duration
+#[poise::command(slash_command, guild_only)]
+pub async fn lock(
+    ctx: Context<'_>,
+    #[description = "The user whose profile to lock"] user: User,
+    #[description = "How long to lock the profile for, e.g. 30m, 12h, 7d"] duration: String,
+    #[description = "The reason for locking the profile"] reason: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let duration = match parse_duration(&duration) {
+        Some(duration) => duration,
+        None => {
+            ctx.say(":x: Invalid duration, use something like 30m, 12h or 7d")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Locks are stored as a TIMESTAMP, which can't go past 2038
+    let locked_until = match time::Duration::try_from(duration)
+        .ok()
+        .and_then(|duration| OffsetDateTime::now_utc().checked_add(duration))
+        .filter(|locked_until| locked_until.unix_timestamp() <= i32::MAX as i64)
+    {
+        Some(locked_until) => locked_until,
+        None => {
+            ctx.say(":x: That duration is too long").await?;
+            return Ok(());
+        }
+    };
+    let db = &ctx.data().database_controller;
+    let moderation = db.moderation_get(user.id.into()).await?.unwrap_or_default();
+    db.moderation_set(
+        user.id.into(),
+        &ProfileModeration {
+            locked_until: Some(locked_until),
+            ..moderation
+        },
+    )
+    .await?;
+
+    log_moderation(
+        ctx,
+        format!(
+            ":lock: {} locked the profile of {} until <t:{}:f> with a reason of '{}'",
+            ctx.author().mention(),
+            user.mention(),
+            locked_until.unix_timestamp(),
+            reason
+        ),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Profile locked until <t:{}:f>!",
+        locked_until.unix_timestamp()
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Unlock a user's about section and pronouns, allowing edits again
+#[poise::command(slash_command, guild_only)]
+pub async fn unlock(
+    ctx: Context<'_>,
+    #[description = "The user whose profile to unlock"] user: User,
+    #[description = "The reason for unlocking the profile"] reason: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let db = &ctx.data().database_controller;
+    let moderation = db.moderation_get(user.id.into()).await?.unwrap_or_default();
+    db.moderation_set(
+        user.id.into(),
+        &ProfileModeration {
+            locked_until: None,
+            ..moderation
+        },
+    )
+    .await?;
+
+    log_moderation(
+        ctx,
+        format!(
+            ":unlock: {} unlocked the profile of {} with a reason of '{}'",
+            ctx.author().mention(),
+            user.mention(),
+            reason
+        ),
+    )
+    .await?;
+
+    ctx.say(":white_check_mark: Profile unlocked!").await?;
+    Ok(())
+}
+
+/// Force actions off for a user, or lift a previous override along with any automatic action ban
+#[poise::command(slash_command, guild_only)]
+pub async fn actions(
+    ctx: Context<'_>,
+    #[description = "The user to change the action override for"] user: User,
+    #[description = "Whether actions should be forced off for this user"] forced_off: bool,
+    #[description = "The reason for the change"] reason: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let db = &ctx.data().database_controller;
+    let moderation = db.moderation_get(user.id.into()).await?.unwrap_or_default();
+    db.moderation_set(
+        user.id.into(),
+        &ProfileModeration {
+            actions_forced_off: forced_off,
+            ..moderation
+        },
+    )
+    .await?;
+
+    // Lifting the override also lifts an automatic ban for spamming actions
+    let lifted_ban = !forced_off
+        && ctx
+            .data()
+            .database_controller
+            .action_ban_clear(user.id.into())
+            .await?;
+
+    log_moderation(
+        ctx,
+        format!(
+            ":shield: {} {} actions for {} with a reason of '{}'",
+            ctx.author().mention(),
+            if forced_off {
+                "forced off"
+            } else if lifted_ban {
+                "lifted the override and action ban on"
+            } else {
+                "lifted the override on"
+            },
+            user.mention(),
+            reason
+        ),
+    )
+    .await?;
+
+    ctx.say(":white_check_mark: Action override updated!")
+        .await?;
+    Ok(())
+}
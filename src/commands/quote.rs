@@ -1,21 +1,229 @@
+use crate::handlers::badges::check_badges;
+use crate::handlers::quote_io::{self, ImportRow, QuoteFormat};
+use crate::handlers::quote_votes::vote_buttons;
+use crate::handlers::{quote_card, quote_of_the_day};
+use crate::paginator::{join_entries, paginate, paginate_entries, paginate_with};
+use crate::structs::quote::{Quote, QuoteFilter};
+use crate::structs::user::QuotePermission;
+use crate::utils::{has_admin_role, parse_date, text_similarity};
 use crate::{Context, Error};
 use poise::CreateReply;
-use serenity::all::{CreateAllowedMentions, Message, User};
+use serenity::all::{
+    Attachment, ButtonStyle, ChannelId, Colour, ComponentInteractionCollector, CreateActionRow,
+    CreateAllowedMentions, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, GuildChannel, Mentionable, Message, User,
+    UserId,
+};
 use sqlx::types::time::OffsetDateTime;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use time::Duration;
+use tracing::warn;
+
+/// Render a quote for display in a message, showing the author as `name` and linking to
+/// `attachments`, which are the quote's attachment URLs refreshed with `AttachmentUrls`
+pub fn format_quote(quote: &Quote, name: &str, attachments: &[String]) -> String {
+    let mut lines = Vec::new();
+
+    if let (Some(reply_username), Some(reply_content)) =
+        (&quote.reply_username, &quote.reply_content)
+    {
+        lines.push(format!(
+            "> :leftwards_arrow_with_hook: Replying to {}: {}",
+            reply_username,
+            truncate(reply_content, 100)
+        ));
+    }
+
+    lines.push(format!("**#{}** {}: {}", quote.quote_id, name, quote.quote));
+
+    for url in attachments {
+        lines.push(format!(":paperclip: {}", url));
+    }
+
+    let mut footer = format!(
+        "Quoted at: <t:{}:f> by <@{}>",
+        quote.added_at.unix_timestamp(),
+        quote.added_by
+    );
+    if let Some(jump_link) = quote.jump_link() {
+        footer.push_str(&format!(" - [Jump to message]({})", jump_link));
+    }
+    lines.push(footer);
+
+    lines.join("\n")
+}
+
+/// Render a quote for display, resolving the current name of its author and working links to
+/// its attachments
+async fn render_quote(ctx: Context<'_>, quote: &Quote) -> String {
+    let attachments = ctx
+        .data()
+        .attachment_urls
+        .refresh(ctx.serenity_context(), &quote.attachment_urls())
+        .await;
+
+    format_quote(quote, &quote_author(ctx, quote).await, &attachments)
+}
+
+/// Render quotes for display, resolving the current name of each author
+async fn format_quotes(ctx: Context<'_>, quotes: &[Quote]) -> Vec<String> {
+    let mut entries = Vec::with_capacity(quotes.len());
+    for quote in quotes {
+        entries.push(render_quote(ctx, quote).await);
+    }
+
+    entries
+}
+
+/// Page through quotes, only rendering the page being shown since rendering can look up names
+/// and attachment links
+async fn paginate_quotes(ctx: Context<'_>, title: &str, quotes: &[Quote]) -> Result<(), Error> {
+    let pages: Vec<&[Quote]> = quotes.chunks(QUOTES_PER_PAGE).collect();
+    paginate_with(ctx, title, pages.len(), |page| {
+        let quotes = pages[page];
+        async move { join_entries(&format_quotes(ctx, quotes).await) }
+    })
+    .await
+}
+
+/// The current display name of a quote's author
+async fn quote_author(ctx: Context<'_>, quote: &Quote) -> String {
+    ctx.data()
+        .display_names
+        .quote_author(ctx.serenity_context(), quote)
+        .await
+}
+
+/// Shorten text to at most `max` characters, adding an ellipsis if it was cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+
+    format!("{}...", text.chars().take(max).collect::<String>())
+}
+
+/// Check that `text` can be the text of a quote, returning the reason it can't otherwise
+fn check_quote_text(text: &str, has_attachments: bool) -> Result<(), String> {
+    // There has to be something to immortalize
+    if text.trim().is_empty() && !has_attachments {
+        return Err(":x: There's nothing to quote".to_string());
+    }
+
+    if text.chars().count() > MAX_QUOTE_LENGTH {
+        return Err(format!(
+            ":x: Quotes can be at most {} characters long",
+            MAX_QUOTE_LENGTH
+        ));
+    }
+
+    Ok(())
+}
+
+/// The longest quote text, as long as the longest message Discord allows
+const MAX_QUOTE_LENGTH: usize = 4000;
+
+/// Discord's limit on the length of a message
+const MAX_LOG_LENGTH: usize = 2000;
+
+/// How much of a quote's text goes into a log message, so the old and new text of an edit both
+/// fit
+const MAX_LOGGED_QUOTE_LENGTH: usize = 800;
+
+/// How many quotes are shown on a single page
+const QUOTES_PER_PAGE: usize = 10;
+
+/// The most quotes `/quote list`, `/quote search` and `/user_quotes` will page through
+const QUOTE_LIST_LIMIT: u32 = 500;
+
+/// How many recently shown quotes `/random_quote` avoids repeating, per channel
+const RECENT_QUOTES_MEMORY: usize = 25;
+
+/// The largest file `/quote import` accepts, in bytes
+const MAX_IMPORT_SIZE: u32 = 10 * 1024 * 1024;
+
+/// How many quotes `/quote top` shows
+const TOP_QUOTES_LIMIT: u32 = 25;
+
+/// How many members each `/quote stats` leaderboard shows
+const STATS_LIMIT: u32 = 10;
+
+/// How similar the text of two quotes of the same person has to be to count as a duplicate
+const NEAR_DUPLICATE_SIMILARITY: f64 = 0.9;
+
+/// How many of the person's most recent quotes are checked for near duplicates
+const NEAR_DUPLICATE_LOOKBACK: u32 = 50;
+
+/// Tell the user a message has already been quoted
+async fn reply_already_quoted(ctx: Context<'_>, existing: &Quote) -> Result<(), Error> {
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                ":x: That's already been immortalized as #{} by <@{}>",
+                existing.quote_id, existing.added_by
+            ))
+            .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+    )
+    .await?;
+
+    Ok(())
+}
 
 #[poise::command(context_menu_command = "Quote User")]
 pub async fn quote_action(
     ctx: Context<'_>,
     #[description = "The target message to quote"] message: Message,
 ) -> Result<(), Error> {
-    let quote = crate::structs::quote::Quote {
+    // Interaction messages don't carry the author's member data, so look up their nickname
+    let nickname = match ctx.guild_id() {
+        Some(guild_id) => guild_id
+            .member(ctx, message.author.id)
+            .await
+            .ok()
+            .and_then(|member| member.nick),
+        None => None,
+    };
+
+    let quote = Quote {
+        quote_id: 0, // Assigned by the database
         user_id: message.author.id.into(),
         username: message.author.name.clone(),
         quote: message.content.clone(),
         added_by: ctx.author().id.into(),
         added_at: OffsetDateTime::now_utc(),
+        // Interaction messages don't carry a guild id, so take it from the context instead
+        guild_id: ctx.guild_id().map(|guild_id| guild_id.into()),
+        channel_id: Some(message.channel_id.into()),
+        message_id: Some(message.id.into()),
+        attachments: if message.attachments.is_empty() {
+            None
+        } else {
+            Some(
+                message
+                    .attachments
+                    .iter()
+                    .map(|attachment| attachment.url.clone())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        },
+        reply_username: message
+            .referenced_message
+            .as_ref()
+            .map(|reply| reply.author.name.clone()),
+        reply_content: message
+            .referenced_message
+            .as_ref()
+            .map(|reply| reply.content.clone()),
+        nickname,
     };
 
+    if let Err(problem) = check_quote_text(&message.content, !message.attachments.is_empty()) {
+        ctx.say(problem).await?;
+        return Ok(());
+    }
+
     if quote.user_id == quote.added_by {
         ctx.say(":x: You can't quote yourself").await?;
         return Ok(());
@@ -27,47 +235,178 @@ pub async fn quote_action(
         return Ok(());
     }
 
-    ctx.data().database_controller.quote_create(quote).await?;
-    ctx.say(":white_check_mark: Okay, I've immortalized that message for you :)")
+    let db = &ctx.data().database_controller;
+
+    // Respect who the quoted member allows to quote them. Members without a profile haven't
+    // opted out of anything
+    if let Some(profile) = db.get_user_by_discord_id(message.author.id.into()).await? {
+        // Go by roles rather than vouch records, which don't cover members vouched for before
+        // they were kept or by hand
+        let roles = &ctx.data().config.roles;
+        let quoter_is_vouched = match ctx.author_member().await {
+            Some(member) => member
+                .roles
+                .iter()
+                .any(|role_id| *role_id == roles.silly_role || *role_id == roles.admin),
+            None => false,
+        };
+
+        if !profile.accepts_quote_from(quoter_is_vouched) {
+            ctx.send(
+                CreateReply::default()
+                    .content(match profile.quotes_allowed {
+                        QuotePermission::VouchedOnly => format!(
+                            ":x: {} only allows vouched members to quote them",
+                            message.author.name
+                        ),
+                        _ => format!(":x: {} doesn't want to be quoted", message.author.name),
+                    })
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    }
+
+    // Each message can only be immortalized once
+    if let Some(existing) = db.quote_get_by_message_id(message.id.into()).await? {
+        reply_already_quoted(ctx, &existing).await?;
+        return Ok(());
+    }
+
+    // Catch near-identical quotes of the same person, e.g. the same thing sent twice
+    if !quote.quote.trim().is_empty() {
+        let recent = db
+            .quote_get_recent_by_user_id(message.author.id.into(), NEAR_DUPLICATE_LOOKBACK)
+            .await?;
+
+        // Comparing against every recent quote adds up for long messages, so keep it off the
+        // async runtime
+        let text = quote.quote.clone();
+        let duplicate = tokio::task::spawn_blocking(move || {
+            recent.into_iter().find(|existing| {
+                text_similarity(&existing.quote, &text)
+                    .is_some_and(|similarity| similarity >= NEAR_DUPLICATE_SIMILARITY)
+            })
+        })
         .await?;
+
+        if let Some(existing) = duplicate {
+            reply_already_quoted(ctx, &existing).await?;
+            return Ok(());
+        }
+    }
+
+    let quote_id = match db.quote_create(quote).await {
+        Ok(quote_id) => quote_id,
+        // Someone else quoted the same message at the same time
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            if let Some(existing) = db.quote_get_by_message_id(message.id.into()).await? {
+                reply_already_quoted(ctx, &existing).await?;
+            }
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    ctx.say(format!(
+        ":white_check_mark: Okay, I've immortalized that message for you as #{} :)",
+        quote_id
+    ))
+    .await?;
+
+    check_badges(ctx.serenity_context(), ctx.data(), message.author.id).await;
+    check_badges(ctx.serenity_context(), ctx.data(), ctx.author().id).await;
     Ok(())
 }
 
-/// Get a random quote
+/// Get a random quote, avoiding the ones recently shown in this channel
 #[poise::command(slash_command)]
-pub async fn random_quote(ctx: Context<'_>) -> Result<(), Error> {
-    let quote = ctx.data().database_controller.quote_get_random().await?;
+pub async fn random_quote(
+    ctx: Context<'_>,
+    #[description = "Only pick quotes of this user"] user: Option<User>,
+    #[description = "Only pick quotes added on or after this date (YYYY-MM-DD, UTC)"] since: Option<
+        String,
+    >,
+    #[description = "Only pick quotes said in this channel"] channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let since = match since.as_deref().map(parse_date) {
+        Some(None) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(":x: Invalid `since` date, use the YYYY-MM-DD format")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some(Some(date)) => Some(date.midnight().assume_utc()),
+        None => None,
+    };
 
-    if let Some(quote) = quote {
-        ctx.send(
-            CreateReply::default()
-                .content(format!(
-                    "{}: {}\nQuoted at: <t:{}:f> by <@{}>",
-                    quote.username,
-                    quote.quote,
-                    quote.added_at.unix_timestamp(),
-                    quote.added_by
-                ))
-                .allowed_mentions(CreateAllowedMentions::new().empty_users()),
-        )
-        .await?;
-    } else {
+    let filter = QuoteFilter {
+        user_id: user.map(|user| user.id.into()),
+        since,
+        channel_id: channel.map(|channel| channel.id.into()),
+        ..Default::default()
+    };
+
+    let channel_id: u64 = ctx.channel_id().into();
+    let recent: Vec<i32> = ctx
+        .data()
+        .recent_quotes
+        .lock()
+        .await
+        .get(&channel_id)
+        .map(|recent| recent.iter().copied().collect())
+        .unwrap_or_default();
+
+    let db = &ctx.data().database_controller;
+    let mut quote = db.quote_get_random(&filter, &recent).await?;
+
+    // Every matching quote was shown recently, so start the rotation over
+    if quote.is_none() && !recent.is_empty() {
+        quote = db.quote_get_random(&filter, &[]).await?;
+    }
+
+    let Some(quote) = quote else {
         ctx.say("No quotes found").await?;
+        return Ok(());
+    };
+
+    {
+        let mut recent_quotes = ctx.data().recent_quotes.lock().await;
+        let recent = recent_quotes.entry(channel_id).or_default();
+        recent.retain(|quote_id| *quote_id != quote.quote_id);
+        recent.push_back(quote.quote_id);
+        while recent.len() > RECENT_QUOTES_MEMORY {
+            recent.pop_front();
+        }
     }
 
+    let votes = db.quote_vote_count(quote.quote_id).await?;
+    ctx.send(
+        CreateReply::default()
+            .content(render_quote(ctx, &quote).await)
+            .components(vote_buttons(quote.quote_id, votes))
+            .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+    )
+    .await?;
+
     Ok(())
 }
 
-/// Get quotes for a user, showing latest 10
+/// Get quotes for a user, newest first
 #[poise::command(slash_command)]
 pub async fn user_quotes(
     ctx: Context<'_>,
     #[description = "The user to get quotes for"] user: User,
 ) -> Result<(), Error> {
+    ctx.defer().await?;
+
     let quotes = ctx
         .data()
         .database_controller
-        .quote_get_by_user_id(user.id.into())
+        .quote_get_recent_by_user_id(user.id.into(), QUOTE_LIST_LIMIT)
         .await?;
 
     if quotes.is_empty() {
@@ -75,22 +414,801 @@ pub async fn user_quotes(
         return Ok(());
     }
 
-    let mut response = String::new();
-    for quote in quotes {
-        response.push_str(&format!(
-            "{}: {}\nQuoted at: <t:{}:f> by <@{}>\n",
-            quote.username,
-            quote.quote,
-            quote.added_at.unix_timestamp(),
-            quote.added_by
+    paginate_quotes(ctx, &format!("Quotes of {}", user.name), &quotes).await
+}
+
+/// Commands for managing individual quotes
+#[poise::command(
+    slash_command,
+    subcommands(
+        "show",
+        "list",
+        "search",
+        "delete",
+        "remove_mine",
+        "edit",
+        "card",
+        "top",
+        "stats",
+        "export",
+        "import",
+        "qotd"
+    )
+)]
+pub async fn quote(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Send a message to the mod-logs channel about a quote change. The change has already been
+/// made by then, so failing to log it only gets a warning
+async fn log_quote_change(ctx: Context<'_>, message: String) {
+    if let Err(e) = ChannelId::new(ctx.data().config.channels.logs_mod)
+        .send_message(
+            ctx.serenity_context(),
+            CreateMessage::new()
+                .content(truncate(&message, MAX_LOG_LENGTH - 3))
+                .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+        )
+        .await
+    {
+        warn!("Couldn't log quote change: {}", e);
+    }
+}
+
+/// Show a quote by its id
+#[poise::command(slash_command)]
+pub async fn show(
+    ctx: Context<'_>,
+    #[description = "The id of the quote to show"] id: i32,
+) -> Result<(), Error> {
+    match ctx.data().database_controller.quote_get_by_id(id).await? {
+        Some(quote) => {
+            let votes = ctx
+                .data()
+                .database_controller
+                .quote_vote_count(quote.quote_id)
+                .await?;
+            ctx.send(
+                CreateReply::default()
+                    .content(render_quote(ctx, &quote).await)
+                    .components(vote_buttons(quote.quote_id, votes))
+                    .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+            )
+            .await?;
+        }
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!(":x: No quote found with id #{}", id))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// List quotes, newest first, optionally filtered
+#[poise::command(slash_command)]
+pub async fn list(
+    ctx: Context<'_>,
+    #[description = "Only show quotes of this user"] user: Option<User>,
+    #[description = "Only show quotes added by this user"] added_by: Option<User>,
+    #[description = "Only show quotes added on or after this date (YYYY-MM-DD, UTC)"] since: Option<
+        String,
+    >,
+    #[description = "Only show quotes added on or before this date (YYYY-MM-DD, UTC)"]
+    until: Option<String>,
+    #[description = "Only show quotes said in this channel"] channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let since = match since.as_deref().map(parse_date) {
+        Some(None) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(":x: Invalid `since` date, use the YYYY-MM-DD format")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some(Some(date)) => Some(date.midnight().assume_utc()),
+        None => None,
+    };
+
+    let until = match until.as_deref().map(parse_date) {
+        Some(None) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(":x: Invalid `until` date, use the YYYY-MM-DD format")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        // Include the whole of the last day
+        Some(Some(date)) => Some(date.midnight().assume_utc() + Duration::days(1)),
+        None => None,
+    };
+
+    let filter = QuoteFilter {
+        user_id: user.map(|user| user.id.into()),
+        added_by: added_by.map(|user| user.id.into()),
+        since,
+        until,
+        channel_id: channel.map(|channel| channel.id.into()),
+    };
+
+    ctx.defer().await?;
+
+    let quotes = ctx
+        .data()
+        .database_controller
+        .quote_list(&filter, QUOTE_LIST_LIMIT)
+        .await?;
+
+    if quotes.is_empty() {
+        ctx.say("No quotes found").await?;
+        return Ok(());
+    }
+
+    paginate_quotes(ctx, "Quotes", &quotes).await
+}
+
+/// Wrap the words of `text` that match any of the search terms in bold
+fn highlight_matches(text: &str, terms: &[String]) -> String {
+    let mut highlighted = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, highlighted: &mut String| {
+        if word.is_empty() {
+            return;
+        }
+
+        let lowercase = word.to_lowercase();
+        if terms
+            .iter()
+            .any(|term| lowercase.starts_with(term.as_str()))
+        {
+            highlighted.push_str(&format!("**{}**", word));
+        } else {
+            highlighted.push_str(word);
+        }
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut highlighted);
+            highlighted.push(c);
+        }
+    }
+    flush(&mut word, &mut highlighted);
+
+    highlighted
+}
+
+/// Split search text into lowercase words, dropping anything the full-text index can't use
+fn search_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+/// Suggest quotes matching what has been typed so far
+async fn autocomplete_quote_text(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    // Require every word, treating the last one as a prefix as it's probably still being typed
+    let terms = search_terms(partial);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let boolean_query = terms
+        .iter()
+        .map(|term| format!("+{}*", term))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    ctx.data()
+        .database_controller
+        .quote_autocomplete(&boolean_query, 25)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        // Discord limits autocomplete choices to 100 characters
+        .map(|quote| truncate(&quote, 97))
+        .collect()
+}
+
+/// Search quotes by their text
+#[poise::command(slash_command)]
+pub async fn search(
+    ctx: Context<'_>,
+    #[description = "The text to search for"]
+    #[autocomplete = "autocomplete_quote_text"]
+    text: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    // Autocompleted choices may have been cut short
+    let query = text.trim_end_matches("...");
+    let quotes = ctx
+        .data()
+        .database_controller
+        .quote_search(query, QUOTE_LIST_LIMIT)
+        .await?;
+
+    if quotes.is_empty() {
+        ctx.say("No quotes found").await?;
+        return Ok(());
+    }
+
+    let terms = search_terms(query);
+    let highlighted: Vec<Quote> = quotes
+        .into_iter()
+        .map(|quote| Quote {
+            quote: highlight_matches(&quote.quote, &terms),
+            ..quote
+        })
+        .collect();
+
+    paginate_quotes(
+        ctx,
+        &format!("Quotes matching \"{}\"", truncate(query, 50)),
+        &highlighted,
+    )
+    .await
+}
+
+/// Delete a quote, allowed for the quoted user, whoever added it, or admins
+#[poise::command(slash_command, guild_only)]
+pub async fn delete(
+    ctx: Context<'_>,
+    #[description = "The id of the quote to delete"] id: i32,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let quote = match ctx.data().database_controller.quote_get_by_id(id).await? {
+        Some(quote) => quote,
+        None => {
+            ctx.say(format!(":x: No quote found with id #{}", id))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let author_id: i64 = ctx.author().id.into();
+    if quote.user_id != author_id && quote.added_by != author_id && !has_admin_role(ctx).await {
+        ctx.say(":x: You can only delete quotes of you, or quotes you added")
+            .await?;
+        return Ok(());
+    }
+
+    ctx.data().database_controller.quote_delete(id).await?;
+
+    ctx.say(format!(":white_check_mark: Quote #{} deleted", id))
+        .await?;
+
+    log_quote_change(
+        ctx,
+        format!(
+            ":wastebasket: {} deleted quote {}",
+            ctx.author().mention(),
+            render_quote(ctx, &quote).await
+        ),
+    )
+    .await;
+    Ok(())
+}
+
+/// Remove every quote of you
+#[poise::command(slash_command, rename = "remove-mine")]
+pub async fn remove_mine(ctx: Context<'_>) -> Result<(), Error> {
+    let db = &ctx.data().database_controller;
+    let count = db.quote_count_by_user_id(ctx.author().id.into()).await?;
+
+    if count == 0 {
+        ctx.send(
+            CreateReply::default()
+                .content("There are no quotes of you")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // Define some unique identifiers for the confirmation buttons
+    let ctx_id = ctx.id();
+    let confirm_button_id = format!("{}confirm", ctx_id);
+    let cancel_button_id = format!("{}cancel", ctx_id);
+
+    let components = CreateActionRow::Buttons(vec![
+        CreateButton::new(&confirm_button_id)
+            .style(ButtonStyle::Danger)
+            .label(format!("Remove {} quotes", count)),
+        CreateButton::new(&cancel_button_id)
+            .style(ButtonStyle::Secondary)
+            .label("Cancel"),
+    ]);
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                ":warning: This will permanently remove all {} quotes of you. To stop new ones from being added, change who can quote you with `/profiles edit`. Are you sure?",
+                count
+            ))
+            .components(vec![components])
+            .ephemeral(true),
+    )
+    .await?;
+
+    // Wait for the author to press one of the buttons
+    let press = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(std::time::Duration::from_secs(60))
+        .await;
+
+    let Some(press) = press else {
+        ctx.send(
+            CreateReply::default()
+                .content(":x: Timed out, your quotes were not removed")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let removed = if press.data.custom_id == confirm_button_id {
+        Some(db.quote_delete_by_user_id(ctx.author().id.into()).await?)
+    } else {
+        None
+    };
+
+    press
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(match removed {
+                        Some(removed) => {
+                            format!(":white_check_mark: Removed {} quotes of you", removed)
+                        }
+                        None => ":x: Cancelled, your quotes were not removed".to_string(),
+                    })
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    if let Some(removed) = removed {
+        log_quote_change(
+            ctx,
+            format!(
+                ":wastebasket: {} removed all {} quotes of themselves",
+                ctx.author().mention(),
+                removed
+            ),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// Edit the text of a quote (admin only)
+#[poise::command(slash_command, guild_only, check = "crate::utils::is_admin")]
+pub async fn edit(
+    ctx: Context<'_>,
+    #[description = "The id of the quote to edit"] id: i32,
+    #[description = "The corrected quote text"] text: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let quote = match ctx.data().database_controller.quote_get_by_id(id).await? {
+        Some(quote) => quote,
+        None => {
+            ctx.say(format!(":x: No quote found with id #{}", id))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(problem) = check_quote_text(&text, quote.attachments.is_some()) {
+        ctx.say(problem).await?;
+        return Ok(());
+    }
+
+    ctx.data()
+        .database_controller
+        .quote_update_text(id, &text)
+        .await?;
+
+    ctx.say(format!(":white_check_mark: Quote #{} updated", id))
+        .await?;
+
+    log_quote_change(
+        ctx,
+        format!(
+            ":pencil: {} edited quote #{} from '{}' to '{}'",
+            ctx.author().mention(),
+            id,
+            truncate(&quote.quote, MAX_LOGGED_QUOTE_LENGTH),
+            truncate(&text, MAX_LOGGED_QUOTE_LENGTH)
+        ),
+    )
+    .await;
+    Ok(())
+}
+
+/// Export every quote as a file (admin only)
+#[poise::command(slash_command, guild_only, check = "crate::utils::is_admin")]
+pub async fn export(
+    ctx: Context<'_>,
+    #[description = "The file format, defaults to JSON"] format: Option<QuoteFormat>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let format = format.unwrap_or(QuoteFormat::Json);
+    let quotes = ctx.data().database_controller.quote_get_all().await?;
+    let file = quote_io::export_quotes(&quotes, format)?;
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(":package: Exported {} quotes", quotes.len()))
+            .attachment(CreateAttachment::bytes(
+                file,
+                format!("quotes.{}", format.extension()),
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Import quotes from a file in the same format as `/quote export` (admin only)
+#[poise::command(slash_command, guild_only, check = "crate::utils::is_admin")]
+pub async fn import(
+    ctx: Context<'_>,
+    #[description = "A .json or .csv file in the same format as /quote export"] file: Attachment,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let Some(format) = QuoteFormat::from_filename(&file.filename) else {
+        ctx.say(":x: The file must be a .json or .csv file").await?;
+        return Ok(());
+    };
+
+    if file.size > MAX_IMPORT_SIZE {
+        ctx.say(format!(
+            ":x: The file can be at most {} MB",
+            MAX_IMPORT_SIZE / 1024 / 1024
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let rows = match quote_io::parse_quotes(&file.download().await?, format) {
+        Ok(rows) => rows,
+        Err(e) => {
+            ctx.say(format!(":x: Couldn't read the file: {}", e))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let db = &ctx.data().database_controller;
+    let mut imported = 0;
+    let mut problems = Vec::new();
+    // The text of every quote per person, both existing and imported, to catch duplicates
+    let mut known_quotes: HashMap<i64, Vec<String>> = HashMap::new();
+
+    for ImportRow { location, quote } in rows {
+        let quote = match quote {
+            Ok(quote) => quote,
+            Err(e) => {
+                problems.push(format!("{}: {}", location, e));
+                continue;
+            }
+        };
+
+        if let Some(message_id) = quote.message_id {
+            if let Some(existing) = db.quote_get_by_message_id(message_id as u64).await? {
+                problems.push(format!(
+                    "{}: skipped, the message is already quote #{}",
+                    location, existing.quote_id
+                ));
+                continue;
+            }
+        }
+
+        let known = match known_quotes.entry(quote.user_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match db.quote_get_by_user_id(quote.user_id as u64).await {
+                Ok(existing) => entry.insert(
+                    existing
+                        .into_iter()
+                        .map(|existing| existing.quote.trim().to_lowercase())
+                        .collect(),
+                ),
+                // One bad row shouldn't stop the rest of the import
+                Err(e) => {
+                    warn!("Couldn't look up quotes to import against: {}", e);
+                    problems.push(format!(
+                        "{}: skipped, couldn't check for existing quotes: {}",
+                        location, e
+                    ));
+                    continue;
+                }
+            },
+        };
+
+        let text = quote.quote.trim().to_lowercase();
+        if !text.is_empty() && known.contains(&text) {
+            problems.push(format!(
+                "{}: skipped, {} already has this quote",
+                location, quote.username
+            ));
+            continue;
+        }
+
+        match db.quote_create(quote).await {
+            Ok(_) => {
+                imported += 1;
+                known.push(text);
+            }
+            // The same message appears twice in the file
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                problems.push(format!(
+                    "{}: skipped, the message is already quoted",
+                    location
+                ));
+            }
+            Err(e) => {
+                warn!("Couldn't import quote: {}", e);
+                problems.push(format!("{}: skipped, couldn't be saved: {}", location, e));
+            }
+        }
+    }
+
+    let mut reply = CreateReply::default()
+        .content(format!(
+            ":white_check_mark: Imported {} quotes, {} rows were skipped",
+            imported,
+            problems.len()
+        ))
+        .ephemeral(true);
+    if !problems.is_empty() {
+        reply = reply.attachment(CreateAttachment::bytes(
+            problems.join("\n"),
+            "import-problems.txt",
         ));
     }
+    ctx.send(reply).await?;
+
+    log_quote_change(
+        ctx,
+        format!(
+            ":inbox_tray: {} imported {} quotes from {}",
+            ctx.author().mention(),
+            imported,
+            file.filename
+        ),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Manage the quote of the day (admin only)
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("skip", "reroll"),
+    check = "crate::utils::is_admin"
+)]
+pub async fn qotd(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Skip today's quote of the day, removing it if it was already posted
+#[poise::command(slash_command, guild_only)]
+pub async fn skip(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let config = &ctx.data().config.quote_of_the_day;
+    if !config.enabled {
+        ctx.say(":x: The quote of the day isn't enabled").await?;
+        return Ok(());
+    }
+    let day = quote_of_the_day::today(config)?;
+    quote_of_the_day::skip(
+        ctx.serenity_context(),
+        &ctx.data().database_controller,
+        config,
+        day,
+    )
+    .await?;
+
+    ctx.say(":white_check_mark: Today's quote of the day was skipped")
+        .await?;
+
+    log_quote_change(
+        ctx,
+        format!(
+            ":fast_forward: {} skipped the quote of the day",
+            ctx.author().mention()
+        ),
+    )
+    .await;
+    Ok(())
+}
+
+/// Pick a different quote of the day, replacing today's if it was already posted
+#[poise::command(slash_command, guild_only)]
+pub async fn reroll(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let config = &ctx.data().config.quote_of_the_day;
+    if !config.enabled {
+        ctx.say(":x: The quote of the day isn't enabled").await?;
+        return Ok(());
+    }
+    let day = quote_of_the_day::today(config)?;
+    quote_of_the_day::post(
+        ctx.serenity_context(),
+        &ctx.data().database_controller,
+        &ctx.data().display_names,
+        &ctx.data().attachment_urls,
+        config,
+        day,
+    )
+    .await?;
+
+    ctx.say(":white_check_mark: Today's quote of the day was rerolled")
+        .await?;
+
+    log_quote_change(
+        ctx,
+        format!(
+            ":game_die: {} rerolled the quote of the day",
+            ctx.author().mention()
+        ),
+    )
+    .await;
+    Ok(())
+}
+
+/// Render a quote as a shareable image
+#[poise::command(slash_command)]
+pub async fn card(
+    ctx: Context<'_>,
+    #[description = "The id of the quote to render"] id: i32,
+) -> Result<(), Error> {
+    let Some(quote) = ctx.data().database_controller.quote_get_by_id(id).await? else {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(":x: No quote found with id #{}", id))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.defer().await?;
+
+    // Members who left or deleted their account can't be looked up, they get a plain avatar
+    let avatar_url = UserId::new(quote.user_id as u64)
+        .to_user(ctx)
+        .await
+        .ok()
+        .map(|user| user.static_face());
+    let avatar = match avatar_url {
+        Some(url) => quote_card::fetch_avatar(&url).await,
+        None => None,
+    };
+
+    let name = quote_author(ctx, &quote).await;
+    let png = tokio::task::spawn_blocking(move || {
+        quote_card::render_card(&quote, &name, avatar.as_ref()).map_err(|e| e.to_string())
+    })
+    .await??;
+
+    ctx.send(
+        CreateReply::default()
+            .attachment(CreateAttachment::bytes(png, format!("quote-{}.png", id))),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, poise::ChoiceParameter)]
+pub enum TopPeriod {
+    #[name = "All time"]
+    AllTime,
+    #[name = "This month"]
+    ThisMonth,
+}
+
+/// Show the most upvoted quotes
+#[poise::command(slash_command)]
+pub async fn top(
+    ctx: Context<'_>,
+    #[description = "Which quotes to rank, defaults to all time"] period: Option<TopPeriod>,
+) -> Result<(), Error> {
+    let period = period.unwrap_or(TopPeriod::AllTime);
+    let since = match period {
+        TopPeriod::AllTime => None,
+        TopPeriod::ThisMonth => {
+            let today = OffsetDateTime::now_utc().date();
+            Some(today.replace_day(1)?.midnight().assume_utc())
+        }
+    };
+
+    let db = &ctx.data().database_controller;
+    let top = db.quote_top(since, TOP_QUOTES_LIMIT).await?;
+
+    let mut entries = Vec::new();
+    for (quote_id, votes) in top {
+        if let Some(quote) = db.quote_get_by_id(quote_id).await? {
+            entries.push(format!(
+                ":arrow_up: **{}**\n{}",
+                votes,
+                render_quote(ctx, &quote).await
+            ));
+        }
+    }
+
+    if entries.is_empty() {
+        ctx.say("No upvoted quotes found").await?;
+        return Ok(());
+    }
+
+    let title = match period {
+        TopPeriod::AllTime => "Top quotes of all time",
+        TopPeriod::ThisMonth => "Top quotes this month",
+    };
+    paginate(ctx, title, paginate_entries(&entries, QUOTES_PER_PAGE)).await
+}
+
+/// Render a leaderboard of members and their counts
+pub fn leaderboard(counts: &[(i64, i64)]) -> String {
+    if counts.is_empty() {
+        return "Nobody yet".to_string();
+    }
+
+    counts
+        .iter()
+        .enumerate()
+        .map(|(rank, (user_id, count))| format!("{}. <@{}> - {}", rank + 1, user_id, count))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Show who gets quoted the most and who quotes the most
+#[poise::command(slash_command)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    let db = &ctx.data().database_controller;
+    let most_quoted = db.quote_most_quoted(STATS_LIMIT).await?;
+    let most_prolific = db.quote_most_prolific(STATS_LIMIT).await?;
+
+    let embed = CreateEmbed::default()
+        .title(":bar_chart: Quote stats")
+        .field("Most quoted", leaderboard(&most_quoted), true)
+        .field("Most prolific quoters", leaderboard(&most_prolific), true)
+        .color(Colour::FABLED_PINK);
 
     ctx.send(
         CreateReply::default()
-            .content(response)
+            .embed(embed)
             .allowed_mentions(CreateAllowedMentions::new().empty_users()),
     )
     .await?;
+
     Ok(())
 }
@@ -1,4 +1,8 @@
-use crate::{structs::vouch::Vouch, Context, Error};
+use crate::{
+    handlers::badges::check_badges,
+    structs::{pronouns::Pronouns, vouch::Vouch},
+    Context, Error,
+};
 use serenity::all::{
     Colour, CreateAllowedMentions, CreateEmbed, CreateEmbedFooter, CreateMessage, Mentionable, User,
 };
@@ -141,9 +145,31 @@ pub async fn approve(
             vouch.vouched_by.mention()
         );
 
+        // Make sure the user has a profile, they may have already set one up while waiting
+        let profile = match ctx
+            .data()
+            .database_controller
+            .get_user_by_discord_id(user.id.into())
+            .await?
+        {
+            Some(profile) => profile,
+            None => {
+                ctx.data()
+                    .database_controller
+                    .create_user(user.id.into(), ctx.data().config.actions.allowed_by_default)
+                    .await?
+            }
+        };
+        let pronouns = Pronouns::from_profile(profile.pronouns.as_deref());
+
         let public_msg = CreateEmbed::default()
             .title(format!("Welcome to sillycord, {}!", user.tag()))
-            .description("Enjoy your stay in our silly little community!")
+            .description(format!(
+                "{} {} just been vouched in! Say hi and help {} settle into our silly little community!",
+                pronouns.subject_capitalized(),
+                pronouns.verb("has", "have"),
+                pronouns.object
+            ))
             .footer(CreateEmbedFooter::new(format!(
                 "Vouched by {} - Approved by {}",
                 vouch.vouched_by.tag(),
@@ -175,10 +201,13 @@ pub async fn approve(
             )
             .await?;
 
+        // Keep a record of the vouch so it can be shown on the user's profile
         ctx.data()
             .database_controller
-            .create_user(user.id.into())
+            .vouch_create(vouch.to_record(ctx.author()))
             .await?;
+        check_badges(ctx.serenity_context(), ctx.data(), vouch.vouched_by.id).await;
+
         ctx.say(":white_check_mark: Vouch approved!").await?;
     } else {
         ctx.say(":x: No vouch found for this user!").await?;
@@ -244,7 +273,7 @@ pub async fn deny(
         );
 
         let log_channel_id = ctx.data().config.channels.logs_mod;
-        let guild = ctx.guild_id().unwrap().clone();
+        let guild = ctx.guild_id().unwrap();
         let channels = guild.channels(ctx.serenity_context()).await?;
         let channel = channels.get(&log_channel_id.into()).unwrap();
 
@@ -1,9 +1,43 @@
-use crate::{handlers, utils::get_rustc_version, Data, Error};
+use crate::{
+    handlers::{self, badges, quote_votes},
+    utils::{find_message_link, get_rustc_version},
+    Data, Error,
+};
 use ::serenity::all::{
-    ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, Mentionable,
+    ActionRowComponent, ButtonKind, ChannelId, Colour, CreateEmbed, CreateEmbedFooter,
+    CreateMessage, Mentionable, Message, MessageId,
 };
 use poise::serenity_prelude::{self as serenity, ActivityData, OnlineStatus};
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// Find the link to the starred message in a starboard post. Starboard bots put it in the
+/// content, the embed or a link button, depending on the bot
+fn starred_message_link(post: &Message) -> Option<(u64, u64)> {
+    let embed_texts = post.embeds.iter().flat_map(|embed| {
+        embed
+            .url
+            .iter()
+            .chain(&embed.description)
+            .map(String::as_str)
+            .chain(embed.fields.iter().map(|field| field.value.as_str()))
+    });
+    let button_urls = post
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+        .filter_map(|component| match component {
+            ActionRowComponent::Button(button) => match &button.data {
+                ButtonKind::Link { url } => Some(url.as_str()),
+                _ => None,
+            },
+            _ => None,
+        });
+
+    std::iter::once(post.content.as_str())
+        .chain(embed_texts)
+        .chain(button_urls)
+        .find_map(find_message_link)
+}
 
 // Create a span for every event
 #[tracing::instrument(skip(ctx, event, _framework, data))]
@@ -85,6 +119,7 @@ pub async fn event_handler(
             data.database_controller
                 .delete_user_by_discord_id(user.id.into())
                 .await?;
+            data.display_names.forget(user.id).await;
 
             ctx.http
                 .send_message(
@@ -98,6 +133,57 @@ pub async fn event_handler(
                 .await?;
         }
 
+        serenity::FullEvent::Message { new_message } => {
+            // The starboard bot's posts link to the starred message, whose author made it onto
+            // the starboard
+            if new_message.channel_id != data.config.channels.starboard
+                || new_message.author.id != data.config.starboard_bot
+            {
+                return Ok(());
+            }
+
+            let Some((channel_id, message_id)) = starred_message_link(new_message) else {
+                warn!(
+                    "Couldn't find the starred message in starboard post {}",
+                    new_message.id
+                );
+                return Ok(());
+            };
+            // The starred message may have been deleted since
+            let starred = match ChannelId::new(channel_id)
+                .message(ctx, MessageId::new(message_id))
+                .await
+            {
+                Ok(starred) => starred,
+                Err(e) => {
+                    warn!("Couldn't fetch starred message {}: {}", message_id, e);
+                    return Ok(());
+                }
+            };
+
+            if let Some(badge) = badges::get_badge(badges::STARBOARD_BADGE) {
+                if !starred.author.bot {
+                    badges::award_badge(ctx, data, starred.author.id, badge).await?;
+                }
+            }
+        }
+
+        serenity::FullEvent::GuildMemberUpdate { event, .. } => {
+            // Nickname changes should show up on quotes right away
+            data.display_names.forget(event.user.id).await;
+        }
+
+        // Upvote buttons on quotes
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Component(press),
+        } if press
+            .data
+            .custom_id
+            .starts_with(quote_votes::VOTE_BUTTON_PREFIX) =>
+        {
+            quote_votes::handle_vote_button(ctx, data, press).await?;
+        }
+
         serenity::FullEvent::ShardsReady { total_shards, .. } => {
             info!("All shards emitted Ready, using {} shards", total_shards);
         }
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::seq::SliceRandom;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::structs::action::{ActionDefinition, ImageProviderKind};
+
+type Config = crate::ActionImages;
+
+/// File extensions the local provider picks images from
+const IMAGE_EXTENSIONS: &[&str] = &["gif", "png", "jpg", "jpeg", "webp"];
+
+/// Why a provider couldn't come up with an image
+#[derive(Debug)]
+pub enum ImageError {
+    /// The request failed or timed out
+    Request(reqwest::Error),
+    /// The provider answered with something that isn't an image
+    InvalidResponse(String),
+    /// The provider has no images for the reaction
+    NoImages(String),
+    /// A local image couldn't be read
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "request failed: {}", e),
+            Self::InvalidResponse(reason) => write!(f, "invalid response: {}", reason),
+            Self::NoImages(reaction) => write!(f, "no images for {:?}", reaction),
+            Self::Io(e) => write!(f, "couldn't read image: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<reqwest::Error> for ImageError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Request(e)
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// An image ready to be attached to a message
+pub struct ActionImage {
+    pub filename: String,
+    pub data: Vec<u8>,
+}
+
+/// A source of images for actions
+#[async_trait]
+pub trait ImageProvider: Send + Sync {
+    /// Get a random image for a reaction, e.g. "hug"
+    async fn random_image(&self, reaction: &str) -> Result<ActionImage, ImageError>;
+}
+
+/// Images from the otakugifs.xyz API
+pub struct OtakuGifs {
+    client: Client,
+}
+
+impl OtakuGifs {
+    pub fn new(timeout: Duration) -> Result<Self, ImageError> {
+        Ok(Self {
+            client: Client::builder().timeout(timeout).build()?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct OtakuGifsResponse {
+    url: String,
+}
+
+#[async_trait]
+impl ImageProvider for OtakuGifs {
+    async fn random_image(&self, reaction: &str) -> Result<ActionImage, ImageError> {
+        let reaction = reaction.to_lowercase().replace(' ', "_");
+
+        let body = self
+            .client
+            .get("https://api.otakugifs.xyz/gif")
+            .query(&[("reaction", &reaction)])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let response: OtakuGifsResponse =
+            serde_json::from_str(&body).map_err(|e| ImageError::InvalidResponse(e.to_string()))?;
+
+        let data = self
+            .client
+            .get(&response.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        if data.is_empty() {
+            return Err(ImageError::InvalidResponse(format!(
+                "{} is empty",
+                response.url
+            )));
+        }
+
+        let filename = response
+            .url
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or("action.gif")
+            .to_string();
+
+        Ok(ActionImage {
+            filename,
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// Curated images on disk, in a folder per reaction, e.g. `<directory>/hug/*.gif`
+pub struct LocalDirectory {
+    directory: PathBuf,
+}
+
+impl LocalDirectory {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ImageProvider for LocalDirectory {
+    async fn random_image(&self, reaction: &str) -> Result<ActionImage, ImageError> {
+        // Reactions come from the config, but never let one point outside the directory
+        if reaction.is_empty() || reaction.contains(['/', '\\', '.']) {
+            return Err(ImageError::NoImages(reaction.to_string()));
+        }
+
+        let mut entries = match tokio::fs::read_dir(self.directory.join(reaction)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ImageError::NoImages(reaction.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut images = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_image = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+                });
+            if is_image {
+                images.push(path);
+            }
+        }
+
+        let Some(path) = images.choose(&mut rand::thread_rng()) else {
+            return Err(ImageError::NoImages(reaction.to_string()));
+        };
+
+        Ok(ActionImage {
+            filename: path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("action.gif")
+                .to_string(),
+            data: tokio::fs::read(path).await?,
+        })
+    }
+}
+
+/// Every configured image provider, tried in order until one comes up with an image
+pub struct ImageProviders {
+    providers: HashMap<ImageProviderKind, Box<dyn ImageProvider>>,
+    default_order: Vec<ImageProviderKind>,
+}
+
+impl ImageProviders {
+    pub fn new(config: &Config) -> Result<Self, ImageError> {
+        let timeout = Duration::from_secs(config.timeout_secs);
+
+        let mut providers: HashMap<ImageProviderKind, Box<dyn ImageProvider>> = HashMap::new();
+        providers.insert(
+            ImageProviderKind::OtakuGifs,
+            Box::new(OtakuGifs::new(timeout)?),
+        );
+        providers.insert(
+            ImageProviderKind::Local,
+            Box::new(LocalDirectory::new(&config.local_directory)),
+        );
+
+        Ok(Self {
+            providers,
+            default_order: config.providers.clone(),
+        })
+    }
+
+    /// Get an image for an action from the first provider that has one, using the action's own
+    /// provider order if it has one
+    pub async fn random_image(&self, action: &ActionDefinition) -> Result<ActionImage, ImageError> {
+        let order = action.providers.as_ref().unwrap_or(&self.default_order);
+        let mut last_error = ImageError::NoImages(action.reaction.clone());
+
+        for kind in order {
+            let Some(provider) = self.providers.get(kind) else {
+                continue;
+            };
+
+            match provider.random_image(&action.reaction).await {
+                Ok(image) => return Ok(image),
+                Err(e) => {
+                    warn!(
+                        "Image provider {:?} failed for {:?}: {}",
+                        kind, action.reaction, e
+                    );
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+}
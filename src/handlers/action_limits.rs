@@ -0,0 +1,313 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use serenity::all::UserId;
+
+type Config = crate::ActionRateLimits;
+
+/// Which limit stopped an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The actor used an action too recently
+    Actor,
+    /// The target received an action too recently
+    Target,
+    /// The actor used too many actions on the target recently
+    Pair,
+}
+
+/// An action that was stopped by a rate limit
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimited {
+    pub limit: Limit,
+    /// How long until the action would be allowed
+    pub retry_after: Duration,
+}
+
+/// Tracks recent action use to enforce the action rate limits. Kept in memory only, a restart
+/// forgets everything but the bans, which live in the database
+#[derive(Default)]
+pub struct ActionLimiter {
+    last_used: HashMap<UserId, Instant>,
+    last_received: HashMap<UserId, Instant>,
+    /// When each actor used an action on each target, oldest first
+    pairs: HashMap<(UserId, UserId), VecDeque<Instant>>,
+    /// When each member hit a limit, oldest first
+    violations: HashMap<UserId, VecDeque<Instant>>,
+}
+
+/// How much of a cooldown started at `since` is left, if any
+fn cooldown_left(since: Option<&Instant>, cooldown: Duration, now: Instant) -> Option<Duration> {
+    since
+        .map(|since| cooldown.saturating_sub(now.duration_since(*since)))
+        .filter(|left| !left.is_zero())
+}
+
+/// Drop the times that fell out of the window
+fn prune(times: &mut VecDeque<Instant>, window: Duration, now: Instant) {
+    while times
+        .front()
+        .is_some_and(|time| now.duration_since(*time) >= window)
+    {
+        times.pop_front();
+    }
+}
+
+impl ActionLimiter {
+    /// Check whether `actor` may use an action on `target` right now. Doesn't count as a use,
+    /// call `record` when letting the action through
+    pub fn check(
+        &mut self,
+        config: &Config,
+        actor: UserId,
+        target: UserId,
+    ) -> Result<(), RateLimited> {
+        let now = Instant::now();
+
+        let actor_cooldown = Duration::from_secs(config.actor_cooldown_secs);
+        if let Some(left) = cooldown_left(self.last_used.get(&actor), actor_cooldown, now) {
+            return Err(RateLimited {
+                limit: Limit::Actor,
+                retry_after: left,
+            });
+        }
+
+        // Using an action on yourself doesn't bother anyone else
+        if actor == target {
+            return Ok(());
+        }
+
+        let target_cooldown = Duration::from_secs(config.target_cooldown_secs);
+        if let Some(left) = cooldown_left(self.last_received.get(&target), target_cooldown, now) {
+            return Err(RateLimited {
+                limit: Limit::Target,
+                retry_after: left,
+            });
+        }
+
+        if config.pair_limit > 0 {
+            let window = Duration::from_secs(config.pair_window_secs);
+            if let Some(times) = self.pairs.get_mut(&(actor, target)) {
+                prune(times, window, now);
+                if times.len() >= config.pair_limit {
+                    let oldest = times[0];
+                    return Err(RateLimited {
+                        limit: Limit::Pair,
+                        retry_after: window.saturating_sub(now.duration_since(oldest)),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count a use of an action towards the limits
+    pub fn record(&mut self, config: &Config, actor: UserId, target: UserId) {
+        let now = Instant::now();
+        self.forget_expired(config, now);
+
+        self.last_used.insert(actor, now);
+        if actor != target {
+            self.last_received.insert(target, now);
+            if config.pair_limit > 0 {
+                self.pairs
+                    .entry((actor, target))
+                    .or_default()
+                    .push_back(now);
+            }
+        }
+    }
+
+    /// Count a member running into a limit. Returns true when they did so often enough to be
+    /// banned, which also starts their count over
+    pub fn record_violation(&mut self, config: &Config, actor: UserId) -> bool {
+        if config.ban_threshold == 0 {
+            return false;
+        }
+
+        let now = Instant::now();
+        let times = self.violations.entry(actor).or_default();
+        prune(times, Duration::from_secs(config.ban_window_secs), now);
+        times.push_back(now);
+
+        if times.len() >= config.ban_threshold {
+            self.violations.remove(&actor);
+            return true;
+        }
+
+        false
+    }
+
+    /// Drop everything that no longer affects any limit, so the maps don't grow forever
+    fn forget_expired(&mut self, config: &Config, now: Instant) {
+        let actor_cooldown = Duration::from_secs(config.actor_cooldown_secs);
+        let target_cooldown = Duration::from_secs(config.target_cooldown_secs);
+        let pair_window = Duration::from_secs(config.pair_window_secs);
+        let ban_window = Duration::from_secs(config.ban_window_secs);
+
+        self.last_used
+            .retain(|_, used| now.duration_since(*used) < actor_cooldown);
+        self.last_received
+            .retain(|_, received| now.duration_since(*received) < target_cooldown);
+        self.pairs.retain(|_, times| {
+            prune(times, pair_window, now);
+            !times.is_empty()
+        });
+        self.violations.retain(|_, times| {
+            prune(times, ban_window, now);
+            !times.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: UserId = UserId::new(1);
+    const BOB: UserId = UserId::new(2);
+    const CAROL: UserId = UserId::new(3);
+
+    /// An actor using an action on a target
+    type Use = (UserId, UserId);
+
+    fn config(actor_cooldown_secs: u64, target_cooldown_secs: u64, pair_limit: usize) -> Config {
+        Config {
+            actor_cooldown_secs,
+            target_cooldown_secs,
+            pair_limit,
+            ..Config::default()
+        }
+    }
+
+    struct Case {
+        name: &'static str,
+        config: Config,
+        /// Uses recorded before checking
+        recorded: &'static [Use],
+        check: Use,
+        expected: Option<Limit>,
+    }
+
+    #[test]
+    fn check() {
+        let cases = [
+            Case {
+                name: "first use",
+                config: config(10, 5, 5),
+                recorded: &[],
+                check: (ALICE, BOB),
+                expected: None,
+            },
+            Case {
+                name: "actor cooldown",
+                config: config(10, 5, 5),
+                recorded: &[(ALICE, BOB)],
+                check: (ALICE, CAROL),
+                expected: Some(Limit::Actor),
+            },
+            Case {
+                name: "actor cooldown on yourself",
+                config: config(10, 5, 5),
+                recorded: &[(ALICE, BOB)],
+                check: (ALICE, ALICE),
+                expected: Some(Limit::Actor),
+            },
+            Case {
+                name: "target cooldown",
+                config: config(10, 5, 5),
+                recorded: &[(ALICE, BOB)],
+                check: (CAROL, BOB),
+                expected: Some(Limit::Target),
+            },
+            Case {
+                name: "using an action on yourself doesn't start a target cooldown",
+                config: config(10, 5, 5),
+                recorded: &[(ALICE, ALICE)],
+                check: (BOB, ALICE),
+                expected: None,
+            },
+            Case {
+                name: "cooldowns turned off",
+                config: config(0, 0, 0),
+                recorded: &[(ALICE, BOB), (ALICE, BOB), (ALICE, BOB)],
+                check: (ALICE, BOB),
+                expected: None,
+            },
+            Case {
+                name: "pair limit",
+                config: config(0, 0, 2),
+                recorded: &[(ALICE, BOB), (ALICE, BOB)],
+                check: (ALICE, BOB),
+                expected: Some(Limit::Pair),
+            },
+            Case {
+                name: "pair limit only counts the same pair",
+                config: config(0, 0, 2),
+                recorded: &[(ALICE, BOB), (ALICE, BOB), (CAROL, BOB)],
+                check: (ALICE, CAROL),
+                expected: None,
+            },
+            Case {
+                name: "under the pair limit",
+                config: config(0, 0, 2),
+                recorded: &[(ALICE, BOB)],
+                check: (ALICE, BOB),
+                expected: None,
+            },
+        ];
+
+        for case in cases {
+            let mut limiter = ActionLimiter::default();
+            for (actor, target) in case.recorded {
+                limiter.record(&case.config, *actor, *target);
+            }
+
+            let (actor, target) = case.check;
+            let result = limiter.check(&case.config, actor, target);
+            assert_eq!(
+                result.err().map(|limited| limited.limit),
+                case.expected,
+                "{}",
+                case.name
+            );
+            if let Err(limited) = result {
+                assert!(!limited.retry_after.is_zero(), "{}", case.name);
+            }
+        }
+    }
+
+    #[test]
+    fn check_doesnt_record() {
+        let config = config(10, 5, 5);
+        let mut limiter = ActionLimiter::default();
+
+        assert!(limiter.check(&config, ALICE, BOB).is_ok());
+        assert!(limiter.check(&config, ALICE, BOB).is_ok());
+    }
+
+    #[test]
+    fn record_violation() {
+        // (ban threshold, violations recorded, expected result of each)
+        let cases: [(usize, &[bool]); 3] = [
+            (3, &[false, false, true]),
+            (2, &[false, true, false, true]),
+            (0, &[false, false, false]),
+        ];
+
+        for (ban_threshold, expected) in cases {
+            let config = Config {
+                ban_threshold,
+                ..Config::default()
+            };
+            let mut limiter = ActionLimiter::default();
+
+            let banned: Vec<bool> = expected
+                .iter()
+                .map(|_| limiter.record_violation(&config, ALICE))
+                .collect();
+            assert_eq!(banned, expected, "ban threshold {}", ban_threshold);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use serenity::all::Context;
+use serenity::futures::lock::Mutex;
+use tracing::warn;
+
+use crate::Error;
+
+/// How long a refreshed URL is trusted. Discord's signed URLs last about a day
+const CACHE_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// Discord refreshes at most this many URLs per request
+const MAX_URLS_PER_REQUEST: usize = 50;
+
+/// Hosts whose attachment URLs are signed and expire
+const DISCORD_CDN_HOSTS: &[&str] = &[
+    "https://cdn.discordapp.com/attachments/",
+    "https://media.discordapp.net/attachments/",
+];
+
+#[derive(Deserialize)]
+struct RefreshResponse {
+    refreshed_urls: Vec<RefreshedUrl>,
+}
+
+#[derive(Deserialize)]
+struct RefreshedUrl {
+    original: String,
+    refreshed: String,
+}
+
+/// Keeps stored Discord attachment URLs working. Discord CDN URLs stop working a day after
+/// they were handed out, so quotes store the original URL and get a fresh one when shown.
+///
+/// Cheap to clone, clones share the same cache.
+#[derive(Clone)]
+pub struct AttachmentUrls {
+    client: Client,
+    cache: Arc<Mutex<HashMap<String, (String, Instant)>>>,
+}
+
+impl AttachmentUrls {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get working versions of `urls`, in the same order. URLs that aren't Discord attachments,
+    /// or that Discord couldn't refresh, are returned as they are
+    pub async fn refresh(&self, ctx: &Context, urls: &[&str]) -> Vec<String> {
+        let mut refreshed: HashMap<String, String> = HashMap::new();
+        let mut stale = Vec::new();
+
+        {
+            let cache = self.cache.lock().await;
+            for url in urls {
+                if !DISCORD_CDN_HOSTS.iter().any(|host| url.starts_with(host)) {
+                    continue;
+                }
+
+                match cache.get(*url) {
+                    Some((fresh, refreshed_at)) if refreshed_at.elapsed() < CACHE_TTL => {
+                        refreshed.insert(url.to_string(), fresh.clone());
+                    }
+                    _ => stale.push(url.to_string()),
+                }
+            }
+        }
+
+        for chunk in stale.chunks(MAX_URLS_PER_REQUEST) {
+            match self.request_refresh(ctx, chunk).await {
+                Ok(urls) => {
+                    let mut cache = self.cache.lock().await;
+                    for url in urls {
+                        cache.insert(
+                            url.original.clone(),
+                            (url.refreshed.clone(), Instant::now()),
+                        );
+                        refreshed.insert(url.original, url.refreshed);
+                    }
+                }
+                Err(e) => warn!("Couldn't refresh attachment URLs: {}", e),
+            }
+        }
+
+        urls.iter()
+            .map(|url| {
+                refreshed
+                    .get(*url)
+                    .cloned()
+                    .unwrap_or_else(|| url.to_string())
+            })
+            .collect()
+    }
+
+    async fn request_refresh(
+        &self,
+        ctx: &Context,
+        urls: &[String],
+    ) -> Result<Vec<RefreshedUrl>, Error> {
+        let body = self
+            .client
+            .post("https://discord.com/api/v10/attachments/refresh-urls")
+            .header("Authorization", ctx.http.token())
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_vec(&json!({ "attachment_urls": urls }))?)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let response: RefreshResponse = serde_json::from_str(&body)?;
+
+        Ok(response.refreshed_urls)
+    }
+}
@@ -0,0 +1,170 @@
+use serenity::all::{
+    ChannelId, Colour, Context, CreateAllowedMentions, CreateEmbed, CreateMessage, Mentionable,
+    UserId,
+};
+use tracing::{error, info};
+
+use crate::{Data, Error};
+
+/// What a user has to do to earn a badge
+pub enum BadgeCriteria {
+    /// Have at least this many of your messages quoted
+    QuotesReceived(i64),
+    /// Quote at least this many messages from others
+    QuotesAdded(i64),
+    /// Perform an action at least this many times
+    ActionsGiven { action: &'static str, count: i64 },
+    /// Vouch for at least this many members who were approved
+    VouchesApproved(i64),
+    /// Awarded directly by an event, e.g. appearing on the starboard
+    Event,
+}
+
+pub struct Badge {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub description: &'static str,
+    pub criteria: BadgeCriteria,
+}
+
+pub const STARBOARD_BADGE: &str = "starboard";
+
+/// Every badge that can be earned, in the order they are shown on profiles
+pub const BADGES: &[Badge] = &[
+    Badge {
+        id: "first_quote",
+        name: "Immortalized",
+        emoji: ":scroll:",
+        description: "Had a message quoted for the first time",
+        criteria: BadgeCriteria::QuotesReceived(1),
+    },
+    Badge {
+        id: "quotable",
+        name: "Quotable",
+        emoji: ":speech_balloon:",
+        description: "Had 25 messages quoted",
+        criteria: BadgeCriteria::QuotesReceived(25),
+    },
+    Badge {
+        id: "quote_collector",
+        name: "Quote Collector",
+        emoji: ":books:",
+        description: "Quoted 25 messages from others",
+        criteria: BadgeCriteria::QuotesAdded(25),
+    },
+    Badge {
+        id: "hugger",
+        name: "Professional Hugger",
+        emoji: ":people_hugging:",
+        description: "Gave 100 hugs",
+        criteria: BadgeCriteria::ActionsGiven {
+            action: "hug",
+            count: 100,
+        },
+    },
+    Badge {
+        id: "welcome_committee",
+        name: "Welcome Committee",
+        emoji: ":handshake:",
+        description: "Vouched for 5 approved members",
+        criteria: BadgeCriteria::VouchesApproved(5),
+    },
+    Badge {
+        id: STARBOARD_BADGE,
+        name: "Star Struck",
+        emoji: ":star2:",
+        description: "Appeared on the starboard",
+        criteria: BadgeCriteria::Event,
+    },
+];
+
+/// Look up a badge definition by its id
+pub fn get_badge(id: &str) -> Option<&'static Badge> {
+    BADGES.iter().find(|badge| badge.id == id)
+}
+
+/// Check whether the user meets the criteria for a badge
+async fn meets_criteria(
+    data: &Data,
+    user_id: u64,
+    criteria: &BadgeCriteria,
+) -> Result<bool, Error> {
+    let db = &data.database_controller;
+
+    Ok(match criteria {
+        BadgeCriteria::QuotesReceived(count) => db.quote_count_by_user_id(user_id).await? >= *count,
+        BadgeCriteria::QuotesAdded(count) => db.quote_count_by_added_by(user_id).await? >= *count,
+        BadgeCriteria::ActionsGiven { action, count } => {
+            db.action_count_given_by_action(user_id, action).await? >= *count
+        }
+        BadgeCriteria::VouchesApproved(count) => {
+            db.vouch_count_by_vouched_by(user_id).await? >= *count
+        }
+        BadgeCriteria::Event => false,
+    })
+}
+
+/// Award a badge to a user and announce it if it was newly earned
+pub async fn award_badge(
+    ctx: &Context,
+    data: &Data,
+    user_id: UserId,
+    badge: &Badge,
+) -> Result<(), Error> {
+    if !data
+        .database_controller
+        .badge_award(user_id.into(), badge.id)
+        .await?
+    {
+        return Ok(());
+    }
+
+    info!("User {} earned badge {}", user_id, badge.id);
+
+    let embed = CreateEmbed::default()
+        .title(format!("{} Badge earned: {}", badge.emoji, badge.name))
+        .description(badge.description)
+        .color(Colour::GOLD);
+
+    ctx.http
+        .send_message(
+            ChannelId::new(data.config.channels.main),
+            vec![],
+            &CreateMessage::default()
+                .content(format!("Congrats {}!", user_id.mention()))
+                .embed(embed)
+                .allowed_mentions(CreateAllowedMentions::new().users(vec![user_id])),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Evaluate every activity based badge for a user, awarding and announcing any newly earned ones
+async fn evaluate_badges(ctx: &Context, data: &Data, user_id: UserId) -> Result<(), Error> {
+    let earned = data
+        .database_controller
+        .badge_get_by_user_id(user_id.into())
+        .await?;
+
+    for badge in BADGES {
+        if earned.iter().any(|earned| earned.badge_id == badge.id) {
+            continue;
+        }
+
+        if meets_criteria(data, user_id.into(), &badge.criteria).await? {
+            award_badge(ctx, data, user_id, badge).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluate badges for a user after some activity. Failures are logged rather than returned,
+/// as a badge should never break the command that triggered it
+pub async fn check_badges(ctx: &Context, data: &Data, user_id: UserId) {
+    if let Err(e) = evaluate_badges(ctx, data, user_id).await {
+        error!("Error evaluating badges for user {}: {:?}", user_id, e);
+    }
+}
@@ -1,7 +1,15 @@
-use crate::structs::quote::Quote;
-use crate::structs::user::User;
+use crate::structs::action::ActionBan;
+use crate::structs::action_event::ActionEvent;
+use crate::structs::badge::UserBadge;
+use crate::structs::quote::{Quote, QuoteFilter, QuoteOfTheDay};
+use crate::structs::user::{ProfileModeration, QuotePermission, User};
+use crate::structs::vouch::VouchRecord;
+use rand::Rng;
+use sqlx::types::time::OffsetDateTime;
 use sqlx::MySqlPool;
+use time::Date;
 
+#[derive(Clone)]
 pub struct DatabaseController {
     db: MySqlPool,
 }
@@ -29,15 +37,31 @@ impl DatabaseController {
                 actions_allowed: user.actions_allowed == Some(1),
                 about: user.about,
                 pronouns: user.pronouns,
+                quotes_allowed: QuotePermission::from_db(user.quotes_allowed),
+                blocked_action_categories: user
+                    .blocked_action_categories
+                    .map(|categories| {
+                        categories
+                            .split(',')
+                            .filter(|category| !category.is_empty())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
             })),
             None => Ok(None),
         }
     }
 
-    pub async fn create_user(&self, discord_id: u64) -> Result<User, sqlx::Error> {
+    pub async fn create_user(
+        &self,
+        discord_id: u64,
+        actions_allowed: bool,
+    ) -> Result<User, sqlx::Error> {
         let user = sqlx::query!(
-            "INSERT INTO users (discord_id) VALUES (?)",
-            discord_id.to_string()
+            "INSERT INTO users (discord_id, actions_allowed) VALUES (?, ?)",
+            discord_id.to_string(),
+            actions_allowed as i8
         )
         .execute(&self.db)
         .await?;
@@ -45,18 +69,23 @@ impl DatabaseController {
         Ok(User {
             id: user.last_insert_id(),
             discord_id,
-            actions_allowed: true,
+            actions_allowed,
             about: None,
             pronouns: None,
+            quotes_allowed: QuotePermission::Everyone,
+            blocked_action_categories: Vec::new(),
         })
     }
 
     pub async fn update_user(&self, user: User) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE users SET actions_allowed = ?, about = ?, pronouns = ? WHERE id = ?",
+            "UPDATE users SET actions_allowed = ?, about = ?, pronouns = ?, quotes_allowed = ?, blocked_action_categories = ? WHERE id = ?",
             user.actions_allowed as i8,
             user.about,
             user.pronouns,
+            user.quotes_allowed.to_db(),
+            (!user.blocked_action_categories.is_empty())
+                .then(|| user.blocked_action_categories.join(",")),
             user.id
         )
         .execute(&self.db)
@@ -65,6 +94,43 @@ impl DatabaseController {
         Ok(())
     }
 
+    /// Get what an admin changed about a member's profile, if anything
+    pub async fn moderation_get(
+        &self,
+        user_id: u64,
+    ) -> Result<Option<ProfileModeration>, sqlx::Error> {
+        let moderation = sqlx::query!(
+            "SELECT * FROM profile_moderation WHERE user_id = ?",
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(moderation.map(|moderation| ProfileModeration {
+            locked_until: moderation.locked_until,
+            actions_forced_off: moderation.actions_forced_off == 1,
+        }))
+    }
+
+    pub async fn moderation_set(
+        &self,
+        user_id: u64,
+        moderation: &ProfileModeration,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO profile_moderation (user_id, locked_until, actions_forced_off) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE locked_until = ?, actions_forced_off = ?",
+            user_id,
+            moderation.locked_until,
+            moderation.actions_forced_off as i8,
+            moderation.locked_until,
+            moderation.actions_forced_off as i8
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn delete_user_by_discord_id(&self, discord_id: u64) -> Result<(), sqlx::Error> {
         sqlx::query!("DELETE FROM users WHERE discord_id = ?", discord_id)
             .execute(&self.db)
@@ -73,37 +139,249 @@ impl DatabaseController {
         Ok(())
     }
 
-    pub async fn kv_set(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "INSERT INTO kv_store (`key`, value) VALUES (?, ?) ON DUPLICATE KEY UPDATE value = ?",
-            key,
-            value,
-            value
+    /// Get every kv entry belonging to the given discord id, i.e. whose key is the id itself or
+    /// starts with "<id>:"
+    pub async fn kv_get_by_discord_id(
+        &self,
+        discord_id: u64,
+    ) -> Result<Vec<(String, Option<String>)>, sqlx::Error> {
+        let kvs = sqlx::query!(
+            "SELECT * FROM kv_store WHERE `key` = ? OR `key` LIKE ?",
+            discord_id.to_string(),
+            format!("{}:%", discord_id)
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(kvs.into_iter().map(|kv| (kv.key, kv.value)).collect())
+    }
+
+    /// Store a new quote, returning its id
+    pub async fn quote_create(&self, quote: Quote) -> Result<i32, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO quotes (user_id, username, quote, added_by, added_at, guild_id, channel_id, message_id, attachments, reply_username, reply_content, nickname) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            quote.user_id,
+            quote.username,
+            quote.quote,
+            quote.added_by,
+            quote.added_at,
+            quote.guild_id,
+            quote.channel_id,
+            quote.message_id,
+            quote.attachments,
+            quote.reply_username,
+            quote.reply_content,
+            quote.nickname
         )
         .execute(&self.db)
         .await?;
 
-        Ok(())
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// Get every quote, oldest first
+    pub async fn quote_get_all(&self) -> Result<Vec<Quote>, sqlx::Error> {
+        sqlx::query_as!(Quote, "SELECT * FROM quotes ORDER BY quote_id")
+            .fetch_all(&self.db)
+            .await
+    }
+
+    pub async fn quote_get_by_id(&self, quote_id: i32) -> Result<Option<Quote>, sqlx::Error> {
+        sqlx::query_as!(Quote, "SELECT * FROM quotes WHERE quote_id = ?", quote_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    pub async fn quote_get_by_message_id(
+        &self,
+        message_id: u64,
+    ) -> Result<Option<Quote>, sqlx::Error> {
+        sqlx::query_as!(
+            Quote,
+            "SELECT * FROM quotes WHERE message_id = ?",
+            message_id
+        )
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Get the most recently added quotes of a user
+    pub async fn quote_get_recent_by_user_id(
+        &self,
+        user_id: u64,
+        limit: u32,
+    ) -> Result<Vec<Quote>, sqlx::Error> {
+        sqlx::query_as!(
+            Quote,
+            "SELECT * FROM quotes WHERE user_id = ? ORDER BY added_at DESC LIMIT ?",
+            user_id,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
     }
 
-    pub async fn kv_get(&self, key: &str) -> Result<Option<String>, sqlx::Error> {
-        let kv = sqlx::query!("SELECT * FROM kv_store WHERE `key` = ?", key)
+    /// Get a random quote matching the filter, leaving out the excluded quote ids.
+    ///
+    /// Rather than sorting the whole table randomly, this picks a random id between the lowest
+    /// and highest matching ids and takes the first matching quote from there, wrapping around to
+    /// the start. Quotes following a gap of deleted ids are slightly more likely to be picked.
+    pub async fn quote_get_random(
+        &self,
+        filter: &QuoteFilter,
+        exclude: &[i32],
+    ) -> Result<Option<Quote>, sqlx::Error> {
+        let range = sqlx::query!(
+            "SELECT MIN(quote_id) AS min_id, MAX(quote_id) AS max_id FROM quotes WHERE (? IS NULL OR user_id = ?) AND (? IS NULL OR added_by = ?) AND (? IS NULL OR added_at >= ?) AND (? IS NULL OR added_at < ?) AND (? IS NULL OR channel_id = ?)",
+            filter.user_id,
+            filter.user_id,
+            filter.added_by,
+            filter.added_by,
+            filter.since,
+            filter.since,
+            filter.until,
+            filter.until,
+            filter.channel_id,
+            filter.channel_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let (Some(min_id), Some(max_id)) = (range.min_id, range.max_id) else {
+            return Ok(None);
+        };
+
+        let pivot = rand::thread_rng().gen_range(min_id..=max_id);
+        let exclude = exclude
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        for from_id in [pivot, min_id] {
+            let quote = sqlx::query_as!(
+                Quote,
+                "SELECT * FROM quotes WHERE quote_id >= ? AND NOT FIND_IN_SET(quote_id, ?) AND (? IS NULL OR user_id = ?) AND (? IS NULL OR added_by = ?) AND (? IS NULL OR added_at >= ?) AND (? IS NULL OR added_at < ?) AND (? IS NULL OR channel_id = ?) ORDER BY quote_id LIMIT 1",
+                from_id,
+                exclude,
+                filter.user_id,
+                filter.user_id,
+                filter.added_by,
+                filter.added_by,
+                filter.since,
+                filter.since,
+                filter.until,
+                filter.until,
+                filter.channel_id,
+                filter.channel_id
+            )
             .fetch_optional(&self.db)
             .await?;
 
-        match kv {
-            Some(kv) => Ok(kv.value),
-            None => Ok(None),
+            if quote.is_some() {
+                return Ok(quote);
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub async fn quote_get_by_user_id(&self, user_id: u64) -> Result<Vec<Quote>, sqlx::Error> {
+        sqlx::query_as!(
+            Quote,
+            "SELECT * FROM quotes WHERE user_id = ? ORDER BY added_at DESC",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// List quotes matching the filter, newest first
+    pub async fn quote_list(
+        &self,
+        filter: &QuoteFilter,
+        limit: u32,
+    ) -> Result<Vec<Quote>, sqlx::Error> {
+        sqlx::query_as!(
+            Quote,
+            "SELECT * FROM quotes WHERE (? IS NULL OR user_id = ?) AND (? IS NULL OR added_by = ?) AND (? IS NULL OR added_at >= ?) AND (? IS NULL OR added_at < ?) AND (? IS NULL OR channel_id = ?) ORDER BY added_at DESC LIMIT ?",
+            filter.user_id,
+            filter.user_id,
+            filter.added_by,
+            filter.added_by,
+            filter.since,
+            filter.since,
+            filter.until,
+            filter.until,
+            filter.channel_id,
+            filter.channel_id,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Search quotes by their text using the full-text index, most relevant first
+    pub async fn quote_search(&self, text: &str, limit: u32) -> Result<Vec<Quote>, sqlx::Error> {
+        let quotes = sqlx::query_as!(
+            Quote,
+            "SELECT * FROM quotes WHERE MATCH(quote) AGAINST (? IN NATURAL LANGUAGE MODE) ORDER BY MATCH(quote) AGAINST (? IN NATURAL LANGUAGE MODE) DESC LIMIT ?",
+            text,
+            text,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        if !quotes.is_empty() {
+            return Ok(quotes);
         }
+
+        // Words that are too short or too common aren't in the full-text index, so fall back
+        // to a plain substring search before giving up
+        sqlx::query_as!(
+            Quote,
+            "SELECT * FROM quotes WHERE quote LIKE ? ORDER BY added_at DESC LIMIT ?",
+            format!(
+                "%{}%",
+                text.replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            ),
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Get the text of quotes matching a boolean mode full-text query, for autocompletion
+    pub async fn quote_autocomplete(
+        &self,
+        boolean_query: &str,
+        limit: u32,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let quotes = sqlx::query!(
+            "SELECT quote FROM quotes WHERE MATCH(quote) AGAINST (? IN BOOLEAN MODE) LIMIT ?",
+            boolean_query,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(quotes.into_iter().map(|q| q.quote).collect())
     }
 
-    pub async fn quote_create(&self, quote: Quote) -> Result<(), sqlx::Error> {
+    pub async fn quote_get_by_added_by(&self, added_by: u64) -> Result<Vec<Quote>, sqlx::Error> {
+        sqlx::query_as!(Quote, "SELECT * FROM quotes WHERE added_by = ?", added_by)
+            .fetch_all(&self.db)
+            .await
+    }
+
+    pub async fn quote_update_text(&self, quote_id: i32, quote: &str) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "INSERT INTO quotes (user_id, username, quote, added_by) VALUES (?, ?, ?, ?)",
-            quote.user_id,
-            quote.username,
-            quote.quote,
-            quote.added_by
+            "UPDATE quotes SET quote = ? WHERE quote_id = ?",
+            quote,
+            quote_id
         )
         .execute(&self.db)
         .await?;
@@ -111,39 +389,629 @@ impl DatabaseController {
         Ok(())
     }
 
-    pub async fn quote_get_random(&self) -> Result<Option<Quote>, sqlx::Error> {
-        let quote =
-            sqlx::query!("SELECT * FROM quotes ORDER BY RAND() LIMIT 1").fetch_one(&self.db);
+    pub async fn quote_delete(&self, quote_id: i32) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
 
-        match quote.await {
-            Ok(q) => Ok(Some(Quote {
-                user_id: q.user_id,
-                username: q.username,
-                quote: q.quote,
-                added_by: q.added_by,
-                added_at: q.added_at,
-            })),
-            Err(sqlx::Error::RowNotFound) => Ok(None),
-            Err(e) => Err(e),
-        }
+        sqlx::query!("DELETE FROM quote_votes WHERE quote_id = ?", quote_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Keep the day in the quote of the day history, but without the deleted quote
+        sqlx::query!(
+            "UPDATE quote_of_the_day SET quote_id = NULL WHERE quote_id = ?",
+            quote_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM quotes WHERE quote_id = ?", quote_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
     }
 
-    pub async fn quote_get_by_user_id(&self, user_id: u64) -> Result<Vec<Quote>, sqlx::Error> {
-        let quote = sqlx::query!("SELECT * FROM quotes WHERE user_id = ?", user_id)
-            .fetch_all(&self.db)
+    /// Delete every quote of a user, returning how many were removed
+    pub async fn quote_delete_by_user_id(&self, user_id: u64) -> Result<u64, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM quote_votes WHERE quote_id IN (SELECT quote_id FROM quotes WHERE user_id = ?)",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE quote_of_the_day SET quote_id = NULL WHERE quote_id IN (SELECT quote_id FROM quotes WHERE user_id = ?)",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted = sqlx::query!("DELETE FROM quotes WHERE user_id = ?", user_id)
+            .execute(&mut *tx)
             .await?;
 
-        let mut quotes = Vec::new();
-        for q in quote {
-            quotes.push(Quote {
-                user_id: q.user_id,
-                username: q.username,
-                quote: q.quote,
-                added_by: q.added_by,
-                added_at: q.added_at,
-            });
+        tx.commit().await?;
+
+        Ok(deleted.rows_affected())
+    }
+
+    /// Add the user's upvote to a quote, or take it back if they already upvoted it.
+    /// Returns whether the quote is now upvoted by the user
+    pub async fn quote_vote_toggle(
+        &self,
+        quote_id: i32,
+        user_id: u64,
+    ) -> Result<bool, sqlx::Error> {
+        let removed = sqlx::query!(
+            "DELETE FROM quote_votes WHERE quote_id = ? AND user_id = ?",
+            quote_id,
+            user_id
+        )
+        .execute(&self.db)
+        .await?;
+
+        if removed.rows_affected() > 0 {
+            return Ok(false);
         }
 
-        Ok(quotes)
+        sqlx::query!(
+            "INSERT IGNORE INTO quote_votes (quote_id, user_id) VALUES (?, ?)",
+            quote_id,
+            user_id
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(true)
+    }
+
+    pub async fn quote_vote_count(&self, quote_id: i32) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM quote_votes WHERE quote_id = ?",
+            quote_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    /// Get the ids of the quotes a member upvoted along with when they did, oldest first
+    pub async fn quote_vote_get_by_user_id(
+        &self,
+        user_id: u64,
+    ) -> Result<Vec<(i32, OffsetDateTime)>, sqlx::Error> {
+        let votes = sqlx::query!(
+            "SELECT quote_id, voted_at FROM quote_votes WHERE user_id = ? ORDER BY voted_at",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(votes
+            .into_iter()
+            .map(|vote| (vote.quote_id, vote.voted_at))
+            .collect())
+    }
+
+    /// Get the ids of the most upvoted quotes along with their vote count, optionally only
+    /// counting quotes added since the given time
+    pub async fn quote_top(
+        &self,
+        since: Option<OffsetDateTime>,
+        limit: u32,
+    ) -> Result<Vec<(i32, i64)>, sqlx::Error> {
+        let top = sqlx::query!(
+            "SELECT quotes.quote_id, COUNT(*) AS votes FROM quote_votes JOIN quotes ON quotes.quote_id = quote_votes.quote_id WHERE (? IS NULL OR quotes.added_at >= ?) GROUP BY quotes.quote_id ORDER BY votes DESC, quotes.quote_id LIMIT ?",
+            since,
+            since,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(top.into_iter().map(|t| (t.quote_id, t.votes)).collect())
+    }
+
+    /// Get the members with the most quotes of them, along with their quote count
+    pub async fn quote_most_quoted(&self, limit: u32) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+        let counts = sqlx::query!(
+            "SELECT user_id, COUNT(*) AS count FROM quotes GROUP BY user_id ORDER BY count DESC LIMIT ?",
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(counts.into_iter().map(|c| (c.user_id, c.count)).collect())
+    }
+
+    /// Get the members who added the most quotes, along with how many they added. Quotes whose
+    /// adder deleted their data are left out
+    pub async fn quote_most_prolific(&self, limit: u32) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+        let counts = sqlx::query!(
+            "SELECT added_by, COUNT(*) AS count FROM quotes WHERE added_by != 0 GROUP BY added_by ORDER BY count DESC LIMIT ?",
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(counts.into_iter().map(|c| (c.added_by, c.count)).collect())
+    }
+
+    pub async fn qotd_get(&self, day: Date) -> Result<Option<QuoteOfTheDay>, sqlx::Error> {
+        sqlx::query_as!(
+            QuoteOfTheDay,
+            "SELECT day, quote_id, message_id FROM quote_of_the_day WHERE day = ?",
+            day
+        )
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Record the quote of the day, replacing any previous pick for that day
+    pub async fn qotd_set(&self, qotd: QuoteOfTheDay) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO quote_of_the_day (day, quote_id, message_id) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE quote_id = ?, message_id = ?, posted_at = CURRENT_TIMESTAMP",
+            qotd.day,
+            qotd.quote_id,
+            qotd.message_id,
+            qotd.quote_id,
+            qotd.message_id
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get every quote id along with the last day it was the quote of the day, if ever
+    pub async fn qotd_candidates(&self) -> Result<Vec<(i32, Option<Date>)>, sqlx::Error> {
+        let candidates = sqlx::query!(
+            "SELECT quotes.quote_id, MAX(quote_of_the_day.day) AS last_shown FROM quotes LEFT JOIN quote_of_the_day ON quote_of_the_day.quote_id = quotes.quote_id GROUP BY quotes.quote_id"
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(candidates
+            .into_iter()
+            .map(|c| (c.quote_id, c.last_shown))
+            .collect())
+    }
+
+    pub async fn quote_count_by_user_id(&self, user_id: u64) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM quotes WHERE user_id = ?",
+            user_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    pub async fn quote_count_by_added_by(&self, added_by: u64) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM quotes WHERE added_by = ?",
+            added_by
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    pub async fn vouch_create(&self, vouch: VouchRecord) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO vouches (user_id, vouched_by, approved_by, vouched_at, approved_at) VALUES (?, ?, ?, ?, ?)",
+            vouch.user_id,
+            vouch.vouched_by,
+            vouch.approved_by,
+            vouch.vouched_at,
+            vouch.approved_at
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn vouch_get_by_user_id(
+        &self,
+        user_id: u64,
+    ) -> Result<Option<VouchRecord>, sqlx::Error> {
+        let vouch = sqlx::query!(
+            "SELECT * FROM vouches WHERE user_id = ? ORDER BY approved_at DESC LIMIT 1",
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(vouch.map(|v| VouchRecord {
+            user_id: v.user_id,
+            vouched_by: v.vouched_by,
+            approved_by: v.approved_by,
+            vouched_at: v.vouched_at,
+            approved_at: v.approved_at,
+        }))
+    }
+
+    pub async fn vouch_count_by_vouched_by(&self, vouched_by: u64) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM vouches WHERE vouched_by = ?",
+            vouched_by
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    /// Get every vouch the user was vouched in, vouched for, or approved
+    pub async fn vouch_get_all_involving(
+        &self,
+        user_id: u64,
+    ) -> Result<Vec<VouchRecord>, sqlx::Error> {
+        let vouches = sqlx::query!(
+            "SELECT * FROM vouches WHERE user_id = ? OR vouched_by = ? OR approved_by = ?",
+            user_id,
+            user_id,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(vouches
+            .into_iter()
+            .map(|v| VouchRecord {
+                user_id: v.user_id,
+                vouched_by: v.vouched_by,
+                approved_by: v.approved_by,
+                vouched_at: v.vouched_at,
+                approved_at: v.approved_at,
+            })
+            .collect())
+    }
+
+    pub async fn action_event_create(
+        &self,
+        actor_id: u64,
+        target_id: u64,
+        action: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO action_events (actor_id, target_id, action) VALUES (?, ?, ?)",
+            actor_id,
+            target_id,
+            action
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn action_count_given(&self, user_id: u64) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM action_events WHERE actor_id = ?",
+            user_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    pub async fn action_count_received(&self, user_id: u64) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM action_events WHERE target_id = ?",
+            user_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    pub async fn action_count_given_by_action(
+        &self,
+        user_id: u64,
+        action: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM action_events WHERE actor_id = ? AND action = ?",
+            user_id,
+            action
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    pub async fn action_count_received_by_action(
+        &self,
+        user_id: u64,
+        action: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM action_events WHERE target_id = ? AND action = ?",
+            user_id,
+            action
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    /// Count how many times one member used an action on another
+    pub async fn action_count_between(
+        &self,
+        actor_id: u64,
+        target_id: u64,
+        action: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS count FROM action_events WHERE actor_id = ? AND target_id = ? AND action = ?",
+            actor_id,
+            target_id,
+            action
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(count.count)
+    }
+
+    /// Count the actions a member performed, per action
+    pub async fn action_counts_given(
+        &self,
+        user_id: u64,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        let counts = sqlx::query!(
+            "SELECT action, COUNT(*) AS count FROM action_events WHERE actor_id = ? GROUP BY action",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(counts.into_iter().map(|c| (c.action, c.count)).collect())
+    }
+
+    /// Count the actions a member received, per action
+    pub async fn action_counts_received(
+        &self,
+        user_id: u64,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        let counts = sqlx::query!(
+            "SELECT action, COUNT(*) AS count FROM action_events WHERE target_id = ? GROUP BY action",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(counts.into_iter().map(|c| (c.action, c.count)).collect())
+    }
+
+    /// Get the members who performed the most actions, or the most of one action, along with
+    /// how many
+    pub async fn action_top_actors(
+        &self,
+        action: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+        let counts = sqlx::query!(
+            "SELECT actor_id, COUNT(*) AS count FROM action_events WHERE (? IS NULL OR action = ?) GROUP BY actor_id ORDER BY count DESC LIMIT ?",
+            action,
+            action,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(counts.into_iter().map(|c| (c.actor_id, c.count)).collect())
+    }
+
+    /// Get the members who received the most actions, or the most of one action, along with how
+    /// many
+    pub async fn action_top_targets(
+        &self,
+        action: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+        let counts = sqlx::query!(
+            "SELECT target_id, COUNT(*) AS count FROM action_events WHERE (? IS NULL OR action = ?) GROUP BY target_id ORDER BY count DESC LIMIT ?",
+            action,
+            action,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(counts.into_iter().map(|c| (c.target_id, c.count)).collect())
+    }
+
+    /// Get every action the user performed or received
+    pub async fn action_event_get_involving(
+        &self,
+        user_id: u64,
+    ) -> Result<Vec<ActionEvent>, sqlx::Error> {
+        let events = sqlx::query!(
+            "SELECT * FROM action_events WHERE actor_id = ? OR target_id = ? ORDER BY created_at",
+            user_id,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(events
+            .into_iter()
+            .map(|e| ActionEvent {
+                actor_id: e.actor_id,
+                target_id: e.target_id,
+                action: e.action,
+                created_at: e.created_at,
+            })
+            .collect())
+    }
+
+    /// Get the member's action ban, if they're currently banned
+    pub async fn action_ban_get(&self, user_id: u64) -> Result<Option<ActionBan>, sqlx::Error> {
+        let ban = sqlx::query!("SELECT * FROM action_bans WHERE user_id = ?", user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        // Expired bans are left in place until the next one replaces them
+        Ok(ban
+            .filter(|ban| ban.banned_until > OffsetDateTime::now_utc())
+            .map(|ban| ActionBan {
+                user_id: ban.user_id,
+                banned_until: ban.banned_until,
+                reason: ban.reason,
+            }))
+    }
+
+    /// Ban a member from using actions, replacing any ban they already have
+    pub async fn action_ban_set(&self, ban: ActionBan) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO action_bans (user_id, banned_until, reason) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE banned_until = ?, reason = ?, created_at = CURRENT_TIMESTAMP",
+            ban.user_id,
+            ban.banned_until,
+            ban.reason,
+            ban.banned_until,
+            ban.reason
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lift a member's action ban. Returns whether they had one
+    pub async fn action_ban_clear(&self, user_id: u64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM action_bans WHERE user_id = ?", user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn badge_get_by_user_id(&self, user_id: u64) -> Result<Vec<UserBadge>, sqlx::Error> {
+        let badges = sqlx::query!(
+            "SELECT * FROM user_badges WHERE user_id = ? ORDER BY earned_at",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(badges
+            .into_iter()
+            .map(|b| UserBadge {
+                badge_id: b.badge_id,
+                earned_at: b.earned_at,
+            })
+            .collect())
+    }
+
+    /// Award a badge to a user, returning whether it was newly earned
+    pub async fn badge_award(&self, user_id: u64, badge_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT IGNORE INTO user_badges (user_id, badge_id) VALUES (?, ?)",
+            user_id,
+            badge_id
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Remove everything stored about a user in a single transaction.
+    ///
+    /// Rows that are about the user are deleted, while rows that only reference them
+    /// (quotes they added for others, vouches they submitted or approved) are kept but
+    /// have the user's id replaced with 0.
+    pub async fn delete_user_data(&self, discord_id: u64) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM users WHERE discord_id = ?",
+            discord_id.to_string()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM quote_votes WHERE user_id = ? OR quote_id IN (SELECT quote_id FROM quotes WHERE user_id = ?)",
+            discord_id,
+            discord_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE quote_of_the_day SET quote_id = NULL WHERE quote_id IN (SELECT quote_id FROM quotes WHERE user_id = ?)",
+            discord_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM quotes WHERE user_id = ?", discord_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "UPDATE quotes SET added_by = 0 WHERE added_by = ?",
+            discord_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM vouches WHERE user_id = ?", discord_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "UPDATE vouches SET vouched_by = 0 WHERE vouched_by = ?",
+            discord_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE vouches SET approved_by = 0 WHERE approved_by = ?",
+            discord_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM action_events WHERE actor_id = ? OR target_id = ?",
+            discord_id,
+            discord_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM user_badges WHERE user_id = ?", discord_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "DELETE FROM kv_store WHERE `key` = ? OR `key` LIKE ?",
+            discord_id.to_string(),
+            format!("{}:%", discord_id)
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serenity::all::{Context, GuildId, HttpError, UserId};
+use serenity::futures::lock::Mutex;
+
+use crate::structs::quote::Quote;
+
+/// How long a looked up display name is trusted before asking Discord again
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A looked up display name and when it was looked up. `None` means the user is no longer a
+/// member of the guild
+type CachedName = (Option<String>, Instant);
+
+/// Resolves the current display name of members of the main guild, remembering the result so
+/// rendering a page of quotes doesn't look up every member every time.
+///
+/// Cheap to clone, clones share the same cache.
+#[derive(Clone)]
+pub struct DisplayNames {
+    guild_id: GuildId,
+    cache: Arc<Mutex<HashMap<UserId, CachedName>>>,
+}
+
+impl DisplayNames {
+    pub fn new(guild_id: u64) -> Self {
+        Self {
+            guild_id: GuildId::new(guild_id),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the current display name of a member, or `None` if they left the guild
+    pub async fn get(&self, ctx: &Context, user_id: UserId) -> Option<String> {
+        let cached = ctx.cache.guild(self.guild_id).and_then(|guild| {
+            guild
+                .members
+                .get(&user_id)
+                .map(|member| member.display_name().to_string())
+        });
+        if cached.is_some() {
+            return cached;
+        }
+
+        if let Some((name, looked_up_at)) = self.cache.lock().await.get(&user_id) {
+            if looked_up_at.elapsed() < CACHE_TTL {
+                return name.clone();
+            }
+        }
+
+        let name = match self.guild_id.member(ctx, user_id).await {
+            Ok(member) => Some(member.display_name().to_string()),
+            // Only remember that someone left when Discord says so, not on any other failure
+            Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(response)))
+                if response.status_code.as_u16() == 404 =>
+            {
+                None
+            }
+            Err(_) => return None,
+        };
+
+        self.cache
+            .lock()
+            .await
+            .insert(user_id, (name.clone(), Instant::now()));
+
+        name
+    }
+
+    /// The name to show for the author of a quote, falling back to the name stored when it was
+    /// quoted for members who left
+    pub async fn quote_author(&self, ctx: &Context, quote: &Quote) -> String {
+        match self.get(ctx, UserId::new(quote.user_id as u64)).await {
+            Some(name) => name,
+            None => quote.stored_name().to_string(),
+        }
+    }
+
+    /// Forget a member's display name, e.g. after they changed their nickname
+    pub async fn forget(&self, user_id: UserId) {
+        self.cache.lock().await.remove(&user_id);
+    }
+}
@@ -13,9 +13,9 @@ pub async fn join_handler(
 ) -> Result<(), Box<dyn Error>> {
     let welcome_msg = CreateEmbed::default()
         .title("Welcome to sillycord!")
-        .description(format!(
+        .description(
             "Welcome! To keep sillycord a safe and fun place, we require all newly invited members to be vouched by a current member. Please wait for a member of the community to vouch for you. If you do not receive a vouch within 24 hours, you will be removed from the server. If you have any questions, feel free to ask a moderator or admin.",
-        ))
+        )
         .color(Colour::PURPLE)
         .footer(CreateEmbedFooter::new(
             "I am a bot, and this action was performed automatically. If you have any questions or concerns, please contact a moderator or admin.",
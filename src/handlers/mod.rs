@@ -1,2 +1,11 @@
+pub mod action_images;
+pub mod action_limits;
+pub mod attachment_urls;
+pub mod badges;
 pub mod db;
+pub mod display_names;
 pub mod join;
+pub mod quote_card;
+pub mod quote_io;
+pub mod quote_of_the_day;
+pub mod quote_votes;
@@ -0,0 +1,214 @@
+use std::io::Cursor;
+
+use ab_glyph::{FontRef, PxScale};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_text_mut, text_size};
+use reqwest::Client;
+use tracing::warn;
+
+use crate::structs::quote::Quote;
+use crate::Error;
+
+const FONT_REGULAR: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+const FONT_BOLD: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans-Bold.ttf");
+
+const WIDTH: u32 = 1000;
+const PADDING: u32 = 56;
+const AVATAR_SIZE: u32 = 112;
+
+const QUOTE_SCALE: f32 = 40.0;
+const QUOTE_LINE_HEIGHT: u32 = 54;
+/// Longer quotes are cut short so the card stays a reasonable size
+const QUOTE_MAX_LINES: usize = 14;
+
+const NAME_SCALE: f32 = 34.0;
+const DATE_SCALE: f32 = 26.0;
+
+const BACKGROUND: Rgba<u8> = Rgba([43, 45, 49, 255]);
+const ACCENT: Rgba<u8> = Rgba([250, 160, 190, 255]);
+const TEXT: Rgba<u8> = Rgba([242, 243, 245, 255]);
+const MUTED: Rgba<u8> = Rgba([148, 155, 164, 255]);
+
+/// Download an avatar to draw on a card. A card without an avatar is better than no card, so
+/// failures are logged and skipped
+pub async fn fetch_avatar(url: &str) -> Option<DynamicImage> {
+    let bytes = match Client::new().get(url).send().await {
+        Ok(response) => response.bytes().await.ok()?,
+        Err(e) => {
+            warn!("Couldn't download avatar {}: {:?}", url, e);
+            return None;
+        }
+    };
+
+    match image::load_from_memory(&bytes) {
+        Ok(avatar) => Some(avatar),
+        Err(e) => {
+            warn!("Couldn't decode avatar {}: {:?}", url, e);
+            None
+        }
+    }
+}
+
+/// Break text into lines that fit within `max_width` pixels, splitting words that are too long
+/// to fit on a line of their own
+fn wrap_text(text: &str, font: &FontRef, scale: PxScale, max_width: u32) -> Vec<String> {
+    let fits = |line: &str| text_size(scale, font, line).0 <= max_width;
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            for c in word.chars() {
+                line.push(c);
+                if !fits(&line) {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Cut the avatar into a circle with a softened edge
+fn circle_avatar(avatar: &DynamicImage) -> RgbaImage {
+    let mut avatar = imageops::resize(
+        &avatar.to_rgba8(),
+        AVATAR_SIZE,
+        AVATAR_SIZE,
+        FilterType::Triangle,
+    );
+
+    let radius = AVATAR_SIZE as f32 / 2.0;
+    for (x, y, pixel) in avatar.enumerate_pixels_mut() {
+        let dx = x as f32 + 0.5 - radius;
+        let dy = y as f32 + 0.5 - radius;
+        let coverage = (radius - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+        pixel[3] = (pixel[3] as f32 * coverage) as u8;
+    }
+
+    avatar
+}
+
+/// Render a quote as a PNG card showing the text, who said it, their avatar and when
+pub fn render_card(
+    quote: &Quote,
+    name: &str,
+    avatar: Option<&DynamicImage>,
+) -> Result<Vec<u8>, Error> {
+    let regular = FontRef::try_from_slice(FONT_REGULAR)?;
+    let bold = FontRef::try_from_slice(FONT_BOLD)?;
+
+    let quote_scale = PxScale::from(QUOTE_SCALE);
+    let text_width = WIDTH - PADDING * 2;
+
+    let mut lines = wrap_text(
+        &format!("\u{201c}{}\u{201d}", quote.quote.trim()),
+        &regular,
+        quote_scale,
+        text_width,
+    );
+    if lines.len() > QUOTE_MAX_LINES {
+        lines.truncate(QUOTE_MAX_LINES);
+        if let Some(last) = lines.last_mut() {
+            last.push_str("...");
+        }
+    }
+
+    let quote_height = lines.len() as u32 * QUOTE_LINE_HEIGHT;
+    let height = PADDING + quote_height + PADDING / 2 + AVATAR_SIZE + PADDING;
+
+    let mut card = RgbaImage::from_pixel(WIDTH, height, BACKGROUND);
+
+    // Accent bar along the left edge
+    for y in 0..height {
+        for x in 0..8 {
+            card.put_pixel(x, y, ACCENT);
+        }
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        draw_text_mut(
+            &mut card,
+            TEXT,
+            PADDING as i32,
+            (PADDING + i as u32 * QUOTE_LINE_HEIGHT) as i32,
+            quote_scale,
+            &regular,
+            line,
+        );
+    }
+
+    let footer_y = PADDING + quote_height + PADDING / 2;
+
+    match avatar {
+        Some(avatar) => imageops::overlay(
+            &mut card,
+            &circle_avatar(avatar),
+            PADDING as i64,
+            footer_y as i64,
+        ),
+        None => {
+            let radius = AVATAR_SIZE as i32 / 2;
+            draw_filled_circle_mut(
+                &mut card,
+                (PADDING as i32 + radius, footer_y as i32 + radius),
+                radius,
+                ACCENT,
+            );
+        }
+    }
+
+    let name_x = (PADDING + AVATAR_SIZE + PADDING / 2) as i32;
+    let date = quote.added_at.date();
+
+    draw_text_mut(
+        &mut card,
+        TEXT,
+        name_x,
+        footer_y as i32 + 22,
+        NAME_SCALE,
+        &bold,
+        name,
+    );
+    draw_text_mut(
+        &mut card,
+        MUTED,
+        name_x,
+        footer_y as i32 + 66,
+        DATE_SCALE,
+        &regular,
+        &format!(
+            "{} {}, {} - #{}",
+            date.month(),
+            date.day(),
+            date.year(),
+            quote.quote_id
+        ),
+    );
+
+    let mut png = Vec::new();
+    card.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+    Ok(png)
+}
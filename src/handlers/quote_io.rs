@@ -0,0 +1,417 @@
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::structs::quote::Quote;
+use crate::utils::parse_date;
+use crate::Error;
+
+/// The largest quote the database can hold, in bytes
+const MAX_QUOTE_LENGTH: usize = 65535;
+
+/// The longest name the database can hold, in characters
+const MAX_NAME_LENGTH: usize = 255;
+
+/// The range of a MySQL TIMESTAMP, in unix seconds
+const TIMESTAMP_RANGE: std::ops::RangeInclusive<i64> = 1..=i32::MAX as i64;
+
+/// The file formats quotes can be exported to and imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum QuoteFormat {
+    #[name = "JSON"]
+    Json,
+    #[name = "CSV"]
+    Csv,
+}
+
+impl QuoteFormat {
+    /// Guess the format of an uploaded file from its name
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let filename = filename.to_lowercase();
+        if filename.ends_with(".json") {
+            Some(Self::Json)
+        } else if filename.ends_with(".csv") {
+            Some(Self::Csv)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// A quote as it appears in an export file. Ids are strings so they survive spreadsheets and
+/// JSON parsers that can't hold 64-bit integers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuoteRecord {
+    /// Ignored when importing, imported quotes get a new id
+    #[serde(default)]
+    pub quote_id: Option<i32>,
+    pub user_id: String,
+    pub username: String,
+    #[serde(default)]
+    pub nickname: Option<String>,
+    pub quote: String,
+    pub added_by: String,
+    /// RFC 3339 timestamp, or a plain YYYY-MM-DD date when importing
+    pub added_at: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// Attachment URLs, one per line
+    #[serde(default)]
+    pub attachments: Option<String>,
+    #[serde(default)]
+    pub reply_username: Option<String>,
+    #[serde(default)]
+    pub reply_content: Option<String>,
+}
+
+impl QuoteRecord {
+    pub fn from_quote(quote: &Quote) -> Result<Self, Error> {
+        Ok(Self {
+            quote_id: Some(quote.quote_id),
+            user_id: quote.user_id.to_string(),
+            username: quote.username.clone(),
+            nickname: quote.nickname.clone(),
+            quote: quote.quote.clone(),
+            added_by: quote.added_by.to_string(),
+            added_at: quote.added_at.format(&Rfc3339)?,
+            guild_id: quote.guild_id.map(|id| id.to_string()),
+            channel_id: quote.channel_id.map(|id| id.to_string()),
+            message_id: quote.message_id.map(|id| id.to_string()),
+            attachments: quote.attachments.clone(),
+            reply_username: quote.reply_username.clone(),
+            reply_content: quote.reply_content.clone(),
+        })
+    }
+
+    /// Validate the record and turn it into a quote ready to be stored
+    pub fn into_quote(self) -> Result<Quote, String> {
+        let user_id = parse_id("user_id", &self.user_id)?;
+        let added_by = parse_id("added_by", &self.added_by)?;
+
+        if self.username.trim().is_empty() {
+            return Err("username is empty".to_string());
+        }
+        check_name_length("username", Some(&self.username))?;
+        check_name_length("nickname", self.nickname.as_deref())?;
+        check_name_length("reply_username", self.reply_username.as_deref())?;
+
+        let attachments = non_empty(self.attachments);
+        if self.quote.trim().is_empty() && attachments.is_none() {
+            return Err("quote is empty and has no attachments".to_string());
+        }
+        if self.quote.len() > MAX_QUOTE_LENGTH {
+            return Err(format!("quote is longer than {} bytes", MAX_QUOTE_LENGTH));
+        }
+
+        let added_at = OffsetDateTime::parse(self.added_at.trim(), &Rfc3339)
+            .ok()
+            .or_else(|| parse_date(&self.added_at).map(|date| date.midnight().assume_utc()))
+            .ok_or_else(|| {
+                format!(
+                    "added_at {:?} is not a date (YYYY-MM-DD) or RFC 3339 timestamp",
+                    self.added_at
+                )
+            })?;
+        if !TIMESTAMP_RANGE.contains(&added_at.unix_timestamp()) {
+            return Err(format!(
+                "added_at {:?} is not between 1970 and 2038",
+                self.added_at
+            ));
+        }
+
+        Ok(Quote {
+            quote_id: 0, // Assigned by the database
+            user_id,
+            username: self.username.trim().to_string(),
+            quote: self.quote,
+            added_by,
+            added_at,
+            guild_id: parse_optional_id("guild_id", self.guild_id)?,
+            channel_id: parse_optional_id("channel_id", self.channel_id)?,
+            message_id: parse_optional_id("message_id", self.message_id)?,
+            attachments,
+            reply_username: non_empty(self.reply_username),
+            reply_content: non_empty(self.reply_content),
+            nickname: non_empty(self.nickname),
+        })
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+fn check_name_length(field: &str, value: Option<&str>) -> Result<(), String> {
+    match value {
+        Some(value) if value.trim().chars().count() > MAX_NAME_LENGTH => Err(format!(
+            "{} is longer than {} characters",
+            field, MAX_NAME_LENGTH
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn parse_id(field: &str, value: &str) -> Result<i64, String> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| i64::try_from(id).ok())
+        .ok_or_else(|| format!("{} {:?} is not a Discord id", field, value))
+}
+
+fn parse_optional_id(field: &str, value: Option<String>) -> Result<Option<i64>, String> {
+    non_empty(value)
+        .map(|value| parse_id(field, &value))
+        .transpose()
+}
+
+/// Write quotes out in the given format
+pub fn export_quotes(quotes: &[Quote], format: QuoteFormat) -> Result<Vec<u8>, Error> {
+    let records = quotes
+        .iter()
+        .map(QuoteRecord::from_quote)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match format {
+        QuoteFormat::Json => Ok(serde_json::to_vec_pretty(&records)?),
+        QuoteFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for record in &records {
+                writer.serialize(record)?;
+            }
+            Ok(writer.into_inner().map_err(|e| e.into_error())?)
+        }
+    }
+}
+
+/// A single row of an imported file
+pub struct ImportRow {
+    /// Where the row was found in the file, e.g. "line 3" or "entry 3"
+    pub location: String,
+    /// The quote, or why the row isn't a valid quote
+    pub quote: Result<Quote, String>,
+}
+
+/// Read quotes from an uploaded file. A file that can't be read at all is an error, a single bad
+/// row only fails that row
+pub fn parse_quotes(data: &[u8], format: QuoteFormat) -> Result<Vec<ImportRow>, Error> {
+    match format {
+        QuoteFormat::Json => {
+            let entries: Vec<serde_json::Value> = serde_json::from_slice(data)?;
+
+            Ok(entries
+                .into_iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    let quote = serde_json::from_value::<QuoteRecord>(entry)
+                        .map_err(|e| e.to_string())
+                        .and_then(QuoteRecord::into_quote);
+                    ImportRow {
+                        location: format!("entry {}", i + 1),
+                        quote,
+                    }
+                })
+                .collect())
+        }
+        QuoteFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(data);
+            let headers = reader.headers()?.clone();
+            let mut quotes = Vec::new();
+
+            for (i, record) in reader.records().enumerate() {
+                // Rows can span several lines, so use the line the row starts on
+                let position = match &record {
+                    Ok(record) => record.position(),
+                    Err(e) => e.position(),
+                };
+                let location = position
+                    .map(|position| format!("line {}", position.line()))
+                    .unwrap_or_else(|| format!("row {}", i + 1));
+
+                let quote = record
+                    .and_then(|record| record.deserialize::<QuoteRecord>(Some(&headers)))
+                    .map_err(|e| e.to_string())
+                    .and_then(QuoteRecord::into_quote);
+                quotes.push(ImportRow { location, quote });
+            }
+
+            Ok(quotes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What a row should parse to: the quote text, or part of the error
+    type Expected = (&'static str, Result<&'static str, &'static str>);
+
+    const CSV_HEADER: &str = "user_id,username,quote,added_by,added_at,attachments\n";
+
+    #[test]
+    fn parse_quotes_cases() {
+        // (format, file, expected rows, or None if the file can't be read at all)
+        let cases: [(QuoteFormat, String, Option<Vec<Expected>>); 9] = [
+            (
+                QuoteFormat::Json,
+                r#"[{"user_id": "1", "username": "alice", "quote": "hi", "added_by": "2", "added_at": "2024-12-01T12:00:00Z"}]"#
+                    .to_string(),
+                Some(vec![("entry 1", Ok("hi"))]),
+            ),
+            (
+                QuoteFormat::Json,
+                r#"[
+                    {"user_id": "1", "username": "alice", "quote": "dated", "added_by": "2", "added_at": "2024-12-01"},
+                    {"user_id": "alice", "username": "alice", "quote": "hi", "added_by": "2", "added_at": "2024-12-01"},
+                    {"user_id": "1", "username": "alice", "added_by": "2", "added_at": "2024-12-01"},
+                    {"user_id": "1", "username": "alice", "quote": "hi", "added_by": "2", "added_at": "yesterday"},
+                    {"user_id": "1", "username": " ", "quote": "hi", "added_by": "2", "added_at": "2024-12-01"},
+                    {"user_id": "1", "username": "alice", "quote": " ", "added_by": "2", "added_at": "2024-12-01"},
+                    {"user_id": "1", "username": "alice", "quote": "hi", "added_by": "2", "added_at": "1969-12-31"},
+                    {"user_id": "1", "username": "alice", "quote": "hi", "added_by": "2", "added_at": "2038-01-20"}
+                ]"#
+                .to_string(),
+                Some(vec![
+                    ("entry 1", Ok("dated")),
+                    ("entry 2", Err("user_id")),
+                    ("entry 3", Err("quote")),
+                    ("entry 4", Err("added_at")),
+                    ("entry 5", Err("username is empty")),
+                    ("entry 6", Err("quote is empty")),
+                    ("entry 7", Err("between 1970 and 2038")),
+                    ("entry 8", Err("between 1970 and 2038")),
+                ]),
+            ),
+            (
+                QuoteFormat::Json,
+                format!(
+                    r#"[
+                        {{"user_id": "1", "username": "{long}", "quote": "hi", "added_by": "2", "added_at": "2024-12-01"}},
+                        {{"user_id": "1", "username": "alice", "nickname": "{long}", "quote": "hi", "added_by": "2", "added_at": "2024-12-01"}},
+                        {{"user_id": "1", "username": "{longest}", "quote": "hi", "added_by": "2", "added_at": "2024-12-01"}}
+                    ]"#,
+                    long = "é".repeat(256),
+                    longest = "é".repeat(255)
+                ),
+                Some(vec![
+                    ("entry 1", Err("username is longer than 255")),
+                    ("entry 2", Err("nickname is longer than 255")),
+                    ("entry 3", Ok("hi")),
+                ]),
+            ),
+            (QuoteFormat::Json, "[]".to_string(), Some(vec![])),
+            (QuoteFormat::Json, "{}".to_string(), None),
+            (QuoteFormat::Json, "not json".to_string(), None),
+            (
+                QuoteFormat::Csv,
+                format!(
+                    "{}1,alice,hi,2,2024-12-01,\n1,alice,\"two\nlines\",2,2024-12-01,\n1,alice,hi,-2,2024-12-01,\n",
+                    CSV_HEADER
+                ),
+                Some(vec![
+                    ("line 2", Ok("hi")),
+                    ("line 3", Ok("two\nlines")),
+                    ("line 5", Err("added_by")),
+                ]),
+            ),
+            (
+                QuoteFormat::Csv,
+                format!(
+                    "{}1,alice,,2,2024-12-01,https://example.com/cat.png\n1,alice,hi\n",
+                    CSV_HEADER
+                ),
+                Some(vec![("line 2", Ok("")), ("line 3", Err("found record with 3 fields"))]),
+            ),
+            (QuoteFormat::Csv, CSV_HEADER.to_string(), Some(vec![])),
+        ];
+
+        for (format, file, expected) in cases {
+            let rows = parse_quotes(file.as_bytes(), format).ok().map(|rows| {
+                rows.into_iter()
+                    .map(|row| (row.location, row.quote.map(|quote| quote.quote)))
+                    .collect::<Vec<_>>()
+            });
+
+            match (rows, expected) {
+                (Some(rows), Some(expected)) => {
+                    assert_eq!(rows.len(), expected.len(), "parsing {:?}", file);
+                    for ((location, quote), (expected_location, expected_quote)) in
+                        rows.iter().zip(expected)
+                    {
+                        assert_eq!(location, expected_location, "parsing {:?}", file);
+                        match (quote, expected_quote) {
+                            (Ok(quote), Ok(expected)) => {
+                                assert_eq!(quote, expected, "{} of {:?}", location, file)
+                            }
+                            (Err(e), Err(expected)) => assert!(
+                                e.contains(expected),
+                                "{} of {:?} failed with {:?}, expected {:?}",
+                                location,
+                                file,
+                                e,
+                                expected
+                            ),
+                            (quote, expected) => panic!(
+                                "{} of {:?} parsed to {:?}, expected {:?}",
+                                location, file, quote, expected
+                            ),
+                        }
+                    }
+                }
+                (None, None) => {}
+                (rows, expected) => panic!(
+                    "parsing {:?} gave {:?}, expected {:?}",
+                    file, rows, expected
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn export_then_import() {
+        let quote = QuoteRecord {
+            quote_id: Some(7),
+            user_id: "1017196087276220447".to_string(),
+            username: "alice".to_string(),
+            nickname: Some("Alice".to_string()),
+            quote: "hello, \"world\"\nsecond line".to_string(),
+            added_by: "2".to_string(),
+            added_at: "2024-12-01T12:00:00Z".to_string(),
+            guild_id: None,
+            channel_id: Some("3".to_string()),
+            message_id: Some("4".to_string()),
+            attachments: Some("https://example.com/a.png\nhttps://example.com/b.png".to_string()),
+            reply_username: None,
+            reply_content: None,
+        }
+        .into_quote()
+        .unwrap();
+
+        for format in [QuoteFormat::Json, QuoteFormat::Csv] {
+            let exported = export_quotes(std::slice::from_ref(&quote), format).unwrap();
+            let rows = parse_quotes(&exported, format).unwrap();
+
+            assert_eq!(rows.len(), 1, "{:?}", format.extension());
+            let imported = rows.into_iter().next().unwrap().quote.unwrap();
+            assert_eq!(imported.user_id, quote.user_id);
+            assert_eq!(imported.quote, quote.quote);
+            assert_eq!(imported.added_at, quote.added_at);
+            assert_eq!(imported.channel_id, quote.channel_id);
+            assert_eq!(imported.attachments, quote.attachments);
+            assert_eq!(imported.nickname, quote.nickname);
+        }
+    }
+}
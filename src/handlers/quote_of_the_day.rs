@@ -0,0 +1,278 @@
+use chrono::{DateTime, Datelike, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use serenity::all::{
+    ChannelId, Colour, Context, CreateAllowedMentions, CreateEmbed, CreateMessage, EditMessage,
+    MessageId,
+};
+use time::Date;
+use tracing::{error, info, warn};
+
+use crate::commands::quote::format_quote;
+use crate::handlers::attachment_urls::AttachmentUrls;
+use crate::handlers::db::DatabaseController;
+use crate::handlers::display_names::DisplayNames;
+use crate::handlers::quote_votes::vote_buttons;
+use crate::structs::quote::QuoteOfTheDay;
+use crate::Error;
+
+type Config = crate::QuoteOfTheDay;
+
+/// The weight of a quote that has never been the quote of the day, the same as one last shown a
+/// year ago
+const NEVER_SHOWN_WEIGHT: u64 = 365;
+
+/// Parse the configured posting time and timezone
+fn parse_schedule(config: &Config) -> Result<(NaiveTime, Tz), Error> {
+    let time = NaiveTime::parse_from_str(&config.time, "%H:%M")
+        .map_err(|e| format!("Invalid quote of the day time {:?}: {}", config.time, e))?;
+    let timezone = config
+        .timezone
+        .parse::<Tz>()
+        .map_err(|e| format!("Invalid quote of the day timezone: {}", e))?;
+
+    Ok((time, timezone))
+}
+
+/// The current day in the guild's timezone
+pub fn today(config: &Config) -> Result<Date, Error> {
+    let (_, timezone) = parse_schedule(config)?;
+    let today = Utc::now().with_timezone(&timezone).date_naive();
+
+    Ok(Date::from_ordinal_date(
+        today.year(),
+        today.ordinal() as u16,
+    )?)
+}
+
+/// The next moment the quote of the day is due, which is today's posting time if it hasn't
+/// passed yet
+fn next_post_time(time: NaiveTime, timezone: Tz) -> DateTime<Utc> {
+    let now = Utc::now();
+    let mut day = now.with_timezone(&timezone).date_naive();
+
+    loop {
+        // A posting time skipped by a DST change falls back to an hour later
+        let local = day.and_time(time);
+        let scheduled = timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                timezone
+                    .from_local_datetime(&(local + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|scheduled| scheduled.with_timezone(&Utc));
+
+        if let Some(scheduled) = scheduled {
+            if scheduled > now {
+                return scheduled;
+            }
+        }
+
+        day = day.succ_opt().expect("date out of range");
+    }
+}
+
+/// Post the quote of the day every day at the configured time. Runs forever, meant to be spawned
+/// as a background task
+pub async fn scheduler(
+    ctx: Context,
+    db: DatabaseController,
+    display_names: DisplayNames,
+    attachment_urls: AttachmentUrls,
+    config: Config,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    let (time, timezone) = match parse_schedule(&config) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            error!("Quote of the day is disabled: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        // Catch up on today's quote if the bot was offline at the posting time
+        if let Err(e) = post_if_due(
+            &ctx,
+            &db,
+            &display_names,
+            &attachment_urls,
+            &config,
+            time,
+            timezone,
+        )
+        .await
+        {
+            error!("Error posting the quote of the day: {:?}", e);
+        }
+
+        let next = next_post_time(time, timezone);
+        let wait = (next - Utc::now()).to_std().unwrap_or_default();
+        info!("Next quote of the day at {}", next);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Post today's quote if the posting time has passed and nothing was picked yet
+async fn post_if_due(
+    ctx: &Context,
+    db: &DatabaseController,
+    display_names: &DisplayNames,
+    attachment_urls: &AttachmentUrls,
+    config: &Config,
+    time: NaiveTime,
+    timezone: Tz,
+) -> Result<(), Error> {
+    let now = Utc::now().with_timezone(&timezone);
+    if now.time() < time {
+        return Ok(());
+    }
+
+    let day = today(config)?;
+    if db.qotd_get(day).await?.is_some() {
+        return Ok(());
+    }
+
+    post(ctx, db, display_names, attachment_urls, config, day).await
+}
+
+/// Pick a quote, favouring ones that haven't been the quote of the day in a long time
+async fn pick_quote(db: &DatabaseController, day: Date) -> Result<Option<i32>, Error> {
+    let candidates = db.qotd_candidates().await?;
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    let weights: Vec<u64> = candidates
+        .iter()
+        .map(|(_, last_shown)| match last_shown {
+            Some(last_shown) => (day - *last_shown)
+                .whole_days()
+                .clamp(0, NEVER_SHOWN_WEIGHT as i64) as u64,
+            None => NEVER_SHOWN_WEIGHT,
+        })
+        .collect();
+
+    let mut rng = thread_rng();
+    let index = match WeightedIndex::new(&weights) {
+        Ok(distribution) => distribution.sample(&mut rng),
+        // Every quote was shown today, e.g. after rerolling a tiny collection
+        Err(_) => rng.gen_range(0..candidates.len()),
+    };
+
+    Ok(Some(candidates[index].0))
+}
+
+fn qotd_embed(
+    quote: &crate::structs::quote::Quote,
+    name: &str,
+    attachments: &[String],
+) -> CreateEmbed {
+    CreateEmbed::default()
+        .title(":sparkles: Quote of the day")
+        .description(format_quote(quote, name, attachments))
+        .color(Colour::FABLED_PINK)
+}
+
+/// Pick and post the quote of the day, replacing the message already posted for the day if any
+pub async fn post(
+    ctx: &Context,
+    db: &DatabaseController,
+    display_names: &DisplayNames,
+    attachment_urls: &AttachmentUrls,
+    config: &Config,
+    day: Date,
+) -> Result<(), Error> {
+    let existing = db.qotd_get(day).await?;
+
+    let Some(quote_id) = pick_quote(db, day).await? else {
+        warn!("No quotes to pick the quote of the day from");
+        return Ok(());
+    };
+    let Some(quote) = db.quote_get_by_id(quote_id).await? else {
+        return Err(format!("Picked quote #{} no longer exists", quote_id).into());
+    };
+
+    let votes = db.quote_vote_count(quote_id).await?;
+    let name = display_names.quote_author(ctx, &quote).await;
+    let attachments = attachment_urls.refresh(ctx, &quote.attachment_urls()).await;
+    let channel = ChannelId::new(config.channel);
+
+    // Edit today's message when rerolling, it may have been deleted by hand in the meantime
+    let mut message_id = None;
+    if let Some(existing_id) = existing.and_then(|existing| existing.message_id) {
+        let edited = channel
+            .edit_message(
+                ctx,
+                MessageId::new(existing_id as u64),
+                EditMessage::new()
+                    .embed(qotd_embed(&quote, &name, &attachments))
+                    .components(vote_buttons(quote_id, votes)),
+            )
+            .await;
+
+        if edited.is_ok() {
+            message_id = Some(existing_id);
+        }
+    }
+
+    if message_id.is_none() {
+        let message = channel
+            .send_message(
+                ctx,
+                CreateMessage::new()
+                    .embed(qotd_embed(&quote, &name, &attachments))
+                    .components(vote_buttons(quote_id, votes))
+                    .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+            )
+            .await?;
+        message_id = Some(message.id.get() as i64);
+    }
+
+    db.qotd_set(QuoteOfTheDay {
+        day,
+        quote_id: Some(quote_id),
+        message_id,
+    })
+    .await?;
+
+    info!("Quote #{} is the quote of the day for {}", quote_id, day);
+
+    Ok(())
+}
+
+/// Skip the quote of the day, removing the posted message so nothing is shown for the day
+pub async fn skip(
+    ctx: &Context,
+    db: &DatabaseController,
+    config: &Config,
+    day: Date,
+) -> Result<(), Error> {
+    if let Some(message_id) = db
+        .qotd_get(day)
+        .await?
+        .and_then(|existing| existing.message_id)
+    {
+        if let Err(e) = ChannelId::new(config.channel)
+            .delete_message(ctx, MessageId::new(message_id as u64))
+            .await
+        {
+            warn!("Couldn't delete the skipped quote of the day: {:?}", e);
+        }
+    }
+
+    db.qotd_set(QuoteOfTheDay {
+        day,
+        quote_id: None,
+        message_id: None,
+    })
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, Context, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use tracing::info;
+
+use crate::{Data, Error};
+
+/// Custom id prefix of the upvote button, followed by the quote id. The button has to keep
+/// working after a restart, so it's handled by the event handler rather than a collector
+pub const VOTE_BUTTON_PREFIX: &str = "quote_vote:";
+
+/// The upvote button shown below a rendered quote
+pub fn vote_buttons(quote_id: i32, votes: i64) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![CreateButton::new(format!(
+        "{}{}",
+        VOTE_BUTTON_PREFIX, quote_id
+    ))
+    .emoji('⬆')
+    .label(votes.to_string())
+    .style(ButtonStyle::Secondary)])]
+}
+
+/// Toggle the presser's upvote on a quote and update the vote count on the button
+pub async fn handle_vote_button(
+    ctx: &Context,
+    data: &Data,
+    press: &ComponentInteraction,
+) -> Result<(), Error> {
+    let Some(quote_id) = press
+        .data
+        .custom_id
+        .strip_prefix(VOTE_BUTTON_PREFIX)
+        .and_then(|id| id.parse::<i32>().ok())
+    else {
+        return Ok(());
+    };
+
+    let db = &data.database_controller;
+
+    if db.quote_get_by_id(quote_id).await?.is_none() {
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(":x: That quote has been deleted")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let voted = db.quote_vote_toggle(quote_id, press.user.id.into()).await?;
+    let votes = db.quote_vote_count(quote_id).await?;
+
+    info!(
+        "User {} {} quote #{}",
+        press.user.id,
+        if voted {
+            "upvoted"
+        } else {
+            "removed their upvote from"
+        },
+        quote_id
+    );
+
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new().components(vote_buttons(quote_id, votes)),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
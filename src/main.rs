@@ -1,13 +1,19 @@
 mod commands;
 mod events;
 mod handlers;
+mod paginator;
 mod structs;
 mod utils;
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 use events::event_handler;
+use handlers::action_images::ImageProviders;
+use handlers::action_limits::ActionLimiter;
+use handlers::attachment_urls::AttachmentUrls;
 use handlers::db::DatabaseController;
+use handlers::display_names::DisplayNames;
 use serde::{Deserialize, Serialize};
 use serenity::{
     all::{ClientBuilder, GatewayIntents},
@@ -15,6 +21,7 @@ use serenity::{
 };
 use sqlx::mysql::MySqlPoolOptions;
 use sqlx::MySqlPool;
+use structs::action::{ActionDefinition, ImageProviderKind};
 use structs::vouch::Vouch;
 use tracing::{event, info, info_span, Level};
 
@@ -84,19 +91,32 @@ struct Data {
     uptime: std::time::Instant,
     config: Config,
     vouch_store: Mutex<Vec<Vouch>>,
+    /// Ids of the quotes recently shown by `/random_quote`, per channel
+    recent_quotes: Mutex<HashMap<u64, VecDeque<i32>>>,
+    display_names: DisplayNames,
+    attachment_urls: AttachmentUrls,
+    action_limiter: Mutex<ActionLimiter>,
+    image_providers: ImageProviders,
 } // User data, which is stored and accessible in all command invocations
 
 pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct Config {
     main_guild_id: u64,
     channels: Channels,
     roles: Roles,
+    /// The bot that reposts starred messages to the starboard channel
+    #[serde(default)]
+    starboard_bot: u64,
+    #[serde(default)]
+    quote_of_the_day: QuoteOfTheDay,
+    #[serde(default)]
+    actions: Actions,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct Channels {
     welcome: u64,
     main: u64,
@@ -105,12 +125,115 @@ struct Channels {
     starboard: u64,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct Roles {
     admin: u64,
     silly_role: u64,
 }
 
+#[derive(Deserialize, Serialize, Clone)]
+struct QuoteOfTheDay {
+    enabled: bool,
+    channel: u64,
+    /// The time to post at, in 24 hour HH:MM format
+    time: String,
+    /// The IANA timezone the time is in, e.g. America/Chicago
+    timezone: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+struct Actions {
+    /// Whether members who never set up a profile accept actions. New profiles start with this
+    /// value too
+    allowed_by_default: bool,
+    /// The actions members can use on each other
+    list: Vec<ActionDefinition>,
+    rate_limits: ActionRateLimits,
+    images: ActionImages,
+    /// How long the target of an action that asks first has to accept it, in seconds
+    request_timeout_secs: u64,
+}
+
+/// Where action GIFs come from
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+struct ActionImages {
+    /// The providers to try, in order, for actions that don't list their own
+    providers: Vec<ImageProviderKind>,
+    /// The folder the local provider reads from, with a folder per reaction inside
+    local_directory: String,
+    /// How long to wait on an online provider before giving up on it, in seconds
+    timeout_secs: u64,
+}
+
+/// Limits on how often actions can be used, to stop members from spamming them at each other.
+/// Setting a limit to 0 turns it off
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+struct ActionRateLimits {
+    /// How long a member has to wait between using actions, in seconds
+    actor_cooldown_secs: u64,
+    /// How long a member has to wait between receiving actions, in seconds
+    target_cooldown_secs: u64,
+    /// How many actions a member can use on the same person within `pair_window_secs`
+    pair_limit: usize,
+    pair_window_secs: u64,
+    /// How many times a member can hit a limit within `ban_window_secs` before they're
+    /// temporarily banned from using actions
+    ban_threshold: usize,
+    ban_window_secs: u64,
+    /// How long an automatic ban lasts, in seconds
+    ban_duration_secs: u64,
+}
+
+impl Default for Actions {
+    fn default() -> Self {
+        Self {
+            allowed_by_default: false,
+            list: ActionDefinition::defaults(),
+            rate_limits: ActionRateLimits::default(),
+            images: ActionImages::default(),
+            request_timeout_secs: 120,
+        }
+    }
+}
+
+impl Default for ActionRateLimits {
+    fn default() -> Self {
+        Self {
+            actor_cooldown_secs: 10,
+            target_cooldown_secs: 5,
+            pair_limit: 5,
+            pair_window_secs: 10 * 60,
+            ban_threshold: 10,
+            ban_window_secs: 10 * 60,
+            ban_duration_secs: 60 * 60,
+        }
+    }
+}
+
+impl Default for ActionImages {
+    fn default() -> Self {
+        Self {
+            providers: vec![ImageProviderKind::OtakuGifs, ImageProviderKind::Local],
+            local_directory: "assets/actions".to_string(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl Default for QuoteOfTheDay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: 0,
+            time: "12:00".to_string(),
+            timezone: "America/Chicago".to_string(),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let start_time = std::time::Instant::now();
@@ -145,6 +268,9 @@ async fn main() {
                     admin: 0,
                     silly_role: 0,
                 },
+                starboard_bot: 0,
+                quote_of_the_day: QuoteOfTheDay::default(),
+                actions: Actions::default(),
             };
             let default_config_toml = toml::to_string_pretty(&default_config).unwrap();
             std::fs::write("config.toml", default_config_toml)
@@ -167,23 +293,26 @@ async fn main() {
         | GatewayIntents::GUILD_MEMBERS
         | GatewayIntents::GUILD_MESSAGES;
 
+    let mut commands = vec![
+        commands::ping::ping(),
+        commands::vouch::vouch(),
+        commands::profile::profiles(),
+        commands::profile::view_profile(),
+        commands::dog::dog(),
+        commands::cta::cta(),
+        commands::eval::eval(),
+        commands::quote::quote_action(),
+        commands::quote::quote(),
+        commands::quote::random_quote(),
+        commands::quote::user_quotes(),
+        commands::cuteness::cutenesss(),
+    ];
+    // Actions are generated from the config
+    commands.extend(commands::action::action_commands(&config.actions.list));
+
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions::<Data, Error> {
-            commands: vec![
-                commands::ping::ping(),
-                commands::vouch::vouch(),
-                commands::profile::profiles(),
-                commands::dog::dog(),
-                commands::cta::cta(),
-                commands::action::use_action_hug(),
-                commands::action::use_action_kiss(),
-                commands::action::use_action_pat(),
-                commands::eval::eval(),
-                commands::quote::quote_action(),
-                commands::quote::random_quote(),
-                commands::quote::user_quotes(),
-                commands::cuteness::cutenesss(),
-            ],
+            commands,
             event_handler: |ctx, event, framework, data| {
                 Box::pin(event_handler(ctx, event, framework, data))
             },
@@ -200,12 +329,31 @@ async fn main() {
         .setup(|ctx, _ready, framework| {
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                let database_controller = DatabaseController::new(pool.clone());
+                let display_names = DisplayNames::new(config.main_guild_id);
+                let attachment_urls = AttachmentUrls::new();
+                let image_providers = ImageProviders::new(&config.actions.images)?;
+
+                // Start background jobs
+                tokio::spawn(handlers::quote_of_the_day::scheduler(
+                    ctx.clone(),
+                    database_controller.clone(),
+                    display_names.clone(),
+                    attachment_urls.clone(),
+                    config.quote_of_the_day.clone(),
+                ));
+
                 Ok(Data {
                     // Initialize user data here
-                    database_controller: DatabaseController::new(pool.clone()),
+                    database_controller,
                     uptime: std::time::Instant::now(),
                     config,
                     vouch_store: Mutex::new(Vec::new()),
+                    recent_quotes: Mutex::new(HashMap::new()),
+                    display_names,
+                    attachment_urls,
+                    action_limiter: Mutex::new(ActionLimiter::default()),
+                    image_providers,
                     // Sticks, Emi, Katie, Eva
                     owners: vec![
                         1017196087276220447,
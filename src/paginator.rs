@@ -0,0 +1,274 @@
+use std::future::Future;
+use std::time::Duration;
+
+use poise::{CreateReply, Modal};
+use serenity::all::{
+    ButtonStyle, Colour, ComponentInteraction, ComponentInteractionCollector, CreateActionRow,
+    CreateAllowedMentions, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+};
+
+use crate::{Context, Error};
+
+/// Discord allows up to 4096 characters in an embed description, leave some room to spare
+const MAX_PAGE_LENGTH: usize = 4000;
+
+/// How long the controls stay active without being used
+const TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Modal)]
+#[name = "Jump to page"]
+struct JumpModal {
+    #[name = "Page number"]
+    #[placeholder = "1"]
+    #[max_length = 6]
+    page: String,
+}
+
+/// Cut an entry short if it's longer than `max` characters
+fn fit_entry(entry: &str, max: usize) -> String {
+    if entry.chars().count() > max {
+        format!(
+            "{}...",
+            entry
+                .chars()
+                .take(max.saturating_sub(3))
+                .collect::<String>()
+        )
+    } else {
+        entry.to_string()
+    }
+}
+
+/// Split entries into pages of at most `per_page` entries, starting a new page early if the
+/// entries wouldn't fit in a single embed
+pub fn paginate_entries(entries: &[String], per_page: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut page = String::new();
+    let mut count = 0;
+
+    for entry in entries {
+        if count > 0 && (count >= per_page || page.len() + entry.len() + 2 > MAX_PAGE_LENGTH) {
+            pages.push(std::mem::take(&mut page));
+            count = 0;
+        }
+
+        if count > 0 {
+            page.push_str("\n\n");
+        }
+
+        // A single entry that's too long for a page is cut short rather than failing to send
+        page.push_str(&fit_entry(entry, MAX_PAGE_LENGTH));
+        count += 1;
+    }
+
+    if count > 0 {
+        pages.push(page);
+    }
+
+    pages
+}
+
+/// Join the entries of a single page, cutting each of them short if they wouldn't all fit in
+/// an embed together
+pub fn join_entries(entries: &[String]) -> String {
+    let separators = entries.len().saturating_sub(1) * 2;
+    let length = entries.iter().map(String::len).sum::<usize>() + separators;
+    if length <= MAX_PAGE_LENGTH {
+        return entries.join("\n\n");
+    }
+
+    let share = (MAX_PAGE_LENGTH - separators) / entries.len();
+    entries
+        .iter()
+        .map(|entry| fit_entry(entry, share))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn page_embed(title: &str, page: &str, current: usize, pages: usize) -> CreateEmbed {
+    CreateEmbed::default()
+        .title(title)
+        .description(page)
+        .footer(CreateEmbedFooter::new(format!(
+            "Page {}/{}",
+            current + 1,
+            pages
+        )))
+        .color(Colour::FABLED_PINK)
+}
+
+fn page_buttons(ctx_id: u64, pages: usize, current: usize) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}first", ctx_id))
+            .emoji('⏮')
+            .style(ButtonStyle::Secondary)
+            .disabled(current == 0),
+        CreateButton::new(format!("{}prev", ctx_id))
+            .emoji('◀')
+            .style(ButtonStyle::Secondary)
+            .disabled(current == 0),
+        CreateButton::new(format!("{}jump", ctx_id))
+            .label(format!("{}/{}", current + 1, pages))
+            .style(ButtonStyle::Primary)
+            .disabled(pages == 1),
+        CreateButton::new(format!("{}next", ctx_id))
+            .emoji('▶')
+            .style(ButtonStyle::Secondary)
+            .disabled(current + 1 >= pages),
+        CreateButton::new(format!("{}last", ctx_id))
+            .emoji('⏭')
+            .style(ButtonStyle::Secondary)
+            .disabled(current + 1 >= pages),
+    ])]
+}
+
+/// Ask the presser which page to jump to, returning the zero-based page index
+async fn ask_jump_page(
+    ctx: Context<'_>,
+    press: ComponentInteraction,
+    pages: usize,
+) -> Result<Option<usize>, Error> {
+    let response =
+        poise::execute_modal_on_component_interaction::<JumpModal>(ctx, press, None, Some(TIMEOUT))
+            .await?;
+
+    Ok(response
+        .and_then(|modal| modal.page.trim().parse::<usize>().ok())
+        .map(|page| page.clamp(1, pages) - 1))
+}
+
+/// Render page `current` the first time it's shown and keep it for later
+async fn rendered_page<'a, F, Fut>(
+    rendered: &'a mut [Option<String>],
+    render_page: &mut F,
+    current: usize,
+) -> &'a str
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = String>,
+{
+    if rendered[current].is_none() {
+        rendered[current] = Some(render_page(current).await);
+    }
+
+    rendered[current].as_deref().unwrap_or_default()
+}
+
+/// Send an embed paginated over `pages` with first/previous/jump/next/last buttons.
+///
+/// Only the author of the command can use the controls, which are removed after a period of
+/// inactivity.
+pub async fn paginate(ctx: Context<'_>, title: &str, pages: Vec<String>) -> Result<(), Error> {
+    paginate_with(ctx, title, pages.len(), |page| {
+        std::future::ready(pages[page].clone())
+    })
+    .await
+}
+
+/// Like [`paginate`], but pages are rendered with `render_page` only once they're shown, for
+/// pages that are expensive to render
+pub async fn paginate_with<F, Fut>(
+    ctx: Context<'_>,
+    title: &str,
+    pages: usize,
+    mut render_page: F,
+) -> Result<(), Error>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = String>,
+{
+    if pages == 0 {
+        return Ok(());
+    }
+
+    // Use the context id as a prefix so we only react to our own buttons
+    let ctx_id = ctx.id();
+    let mut current = 0;
+    let mut rendered = vec![None; pages];
+
+    let page = rendered_page(&mut rendered, &mut render_page, current).await;
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .embed(page_embed(title, page, current, pages))
+                .components(page_buttons(ctx_id, pages, current))
+                .allowed_mentions(CreateAllowedMentions::new().empty_users()),
+        )
+        .await?;
+
+    if pages == 1 {
+        return Ok(());
+    }
+
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(TIMEOUT)
+        .await
+    {
+        if press.user.id != ctx.author().id {
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(
+                                ":x: Only the person who ran the command can use these buttons",
+                            )
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            continue;
+        }
+
+        let action = press.data.custom_id.trim_start_matches(&ctx_id.to_string());
+        if action == "jump" {
+            // The modal answers the interaction
+            match ask_jump_page(ctx, press, pages).await? {
+                Some(page) => current = page,
+                None => continue,
+            }
+        } else {
+            current = match action {
+                "first" => 0,
+                "prev" => current.saturating_sub(1),
+                "next" => (current + 1).min(pages - 1),
+                "last" => pages - 1,
+                _ => continue,
+            };
+
+            // Rendering a new page can take longer than Discord waits for an answer, so the
+            // press is acknowledged first and the message edited afterwards
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    CreateInteractionResponse::Acknowledge,
+                )
+                .await?;
+        }
+
+        let page = rendered_page(&mut rendered, &mut render_page, current).await;
+        reply
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .embed(page_embed(title, page, current, pages))
+                    .components(page_buttons(ctx_id, pages, current)),
+            )
+            .await?;
+    }
+
+    // Remove the controls once they've timed out
+    let page = rendered_page(&mut rendered, &mut render_page, current).await;
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(page_embed(title, page, current, pages))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
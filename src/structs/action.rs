@@ -0,0 +1,197 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serenity::all::UserId;
+use serenity::prelude::Mentionable;
+use time::OffsetDateTime;
+
+use crate::structs::pronouns::Pronouns;
+
+/// An action members can use on each other, e.g. hugging. Actions are listed in the config, and
+/// each one gets a `/action <name>` command and optionally a context menu entry
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ActionDefinition {
+    /// The name of the action, e.g. "hug". Used as the slash command name and recorded with every
+    /// use of the action, so renaming an action resets its stats
+    pub name: String,
+    /// What is said when the action is used, e.g. "{user} pats {target} on {possessive} head".
+    /// `{user}` and `{target}` become mentions, and `{subject}`, `{object}`, `{possessive}` and
+    /// `{reflexive}` the target's pronouns. `{target}` becomes the reflexive pronoun when someone
+    /// uses the action on themselves
+    pub verb: String,
+    /// The verb in the past tense, e.g. "hugged", used to show how often someone received the
+    /// action
+    #[serde(default)]
+    pub past_tense: Option<String>,
+    /// The reaction to fetch a GIF for
+    pub reaction: String,
+    /// Where to get GIFs from, in the order to try them. Uses `actions.images.providers` if not
+    /// given
+    #[serde(default)]
+    pub providers: Option<Vec<ImageProviderKind>>,
+    /// Only allow the action in age restricted channels
+    #[serde(default)]
+    pub nsfw: bool,
+    /// Members can turn off every action in a category, e.g. "romantic". Case doesn't matter, as
+    /// categories are lowercased when loading the config
+    #[serde(deserialize_with = "deserialize_category")]
+    pub category: String,
+    /// Ask the target to accept before posting the GIF. Members who turned actions off can still
+    /// accept these when asked, unless an admin turned actions off for them
+    #[serde(default)]
+    pub ask_first: bool,
+    /// Whether to also add a "<Name> User" entry to the message context menu. Discord only allows
+    /// a few of these, so keep it to the most used actions
+    #[serde(default)]
+    pub context_menu: bool,
+    /// Whether to also add a "<Name>" entry to the user context menu, with the same limits as
+    /// `context_menu`
+    #[serde(default)]
+    pub user_context_menu: bool,
+}
+
+/// The places action GIFs can come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProviderKind {
+    /// The otakugifs.xyz API
+    OtakuGifs,
+    /// Curated GIFs on disk, see `actions.images.local_directory`
+    Local,
+}
+
+/// Categories are compared to what members type, which is lowercased
+fn deserialize_category<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(String::deserialize(deserializer)?.trim().to_lowercase())
+}
+
+impl ActionDefinition {
+    fn new(name: &str, verb: &str, past_tense: &str, category: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            verb: verb.to_string(),
+            past_tense: Some(past_tense.to_string()),
+            reaction: name.to_string(),
+            providers: None,
+            nsfw: false,
+            category: category.to_string(),
+            ask_first: false,
+            context_menu: true,
+            user_context_menu: true,
+        }
+    }
+
+    /// The actions available when the config doesn't list any
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("hug", "{user} hugs {target}", "hugged", "friendly"),
+            Self::new(
+                "kiss",
+                "{user} kisses {target} on {possessive} cheek",
+                "kissed",
+                "romantic",
+            ),
+            Self::new(
+                "pat",
+                "{user} pats {target} on {possessive} head",
+                "patted",
+                "friendly",
+            ),
+        ]
+    }
+
+    /// The name with its first letter capitalized, e.g. "Hug"
+    pub fn label(&self) -> String {
+        let mut chars = self.name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    /// The message announcing that `user` used the action on `targets`, followed by what they
+    /// had to say about it if anything
+    pub fn render(
+        &self,
+        user: UserId,
+        targets: &[UserId],
+        pronouns: &Pronouns,
+        note: Option<&str>,
+    ) -> String {
+        let target = match targets {
+            // Hugging yourself is valid, and deserves proper grammar
+            [target] if *target == user => pronouns.reflexive.clone(),
+            [target] => target.mention().to_string(),
+            [rest @ .., last] => format!(
+                "{} and {}",
+                rest.iter()
+                    .map(|target| target.mention().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                last.mention()
+            ),
+            [] => String::new(),
+        };
+
+        let message = self
+            .verb
+            .replace("{user}", &user.mention().to_string())
+            .replace("{target}", &target)
+            .replace("{subject}", &pronouns.subject)
+            .replace("{object}", &pronouns.object)
+            .replace("{possessive}", &pronouns.possessive)
+            .replace("{reflexive}", &pronouns.reflexive);
+
+        match note.map(str::trim).filter(|note| !note.is_empty()) {
+            Some(note) => format!("{}\n> {}", message, note.replace('\n', "\n> ")),
+            None => message,
+        }
+    }
+
+    /// A line telling how often `target` received the action, e.g. "@X has been hugged 42 times,
+    /// 7 by you". `received` and `from_user` include the current use
+    pub fn render_stats(
+        &self,
+        user: UserId,
+        target: UserId,
+        received: i64,
+        from_user: i64,
+    ) -> String {
+        let times = |count: i64| {
+            if count == 1 {
+                "once".to_string()
+            } else {
+                format!("{} times", count)
+            }
+        };
+
+        let line = match &self.past_tense {
+            Some(past_tense) => format!(
+                "{} has been {} {}",
+                target.mention(),
+                past_tense,
+                times(received)
+            ),
+            None => format!(
+                "{} has received this action {}",
+                target.mention(),
+                times(received)
+            ),
+        };
+
+        if user == target {
+            format!("-# {}", line)
+        } else {
+            format!("-# {}, {} by you", line, times(from_user))
+        }
+    }
+}
+
+/// A temporary ban from using actions
+#[derive(Debug)]
+pub struct ActionBan {
+    pub user_id: i64,
+    pub banned_until: OffsetDateTime,
+    pub reason: String,
+}
@@ -0,0 +1,9 @@
+use time::OffsetDateTime;
+
+#[derive(Debug)]
+pub struct ActionEvent {
+    pub actor_id: i64,
+    pub target_id: i64,
+    pub action: String,
+    pub created_at: OffsetDateTime,
+}
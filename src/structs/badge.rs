@@ -0,0 +1,7 @@
+use time::OffsetDateTime;
+
+#[derive(Debug)]
+pub struct UserBadge {
+    pub badge_id: String,
+    pub earned_at: OffsetDateTime,
+}
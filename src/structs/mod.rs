@@ -1,3 +1,7 @@
+pub mod action;
+pub mod action_event;
+pub mod badge;
+pub mod pronouns;
 pub mod quote;
 pub mod user;
 pub mod vouch;
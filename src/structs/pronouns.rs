@@ -0,0 +1,176 @@
+/// A set of pronouns used to render grammatical messages about a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pronouns {
+    /// e.g. "they" in "they hugged you"
+    pub subject: String,
+    /// e.g. "them" in "you hugged them"
+    pub object: String,
+    /// e.g. "their" in "their head"
+    pub possessive: String,
+    /// e.g. "themselves" in "they hugged themselves"
+    pub reflexive: String,
+    /// Whether verbs should be conjugated in the plural form ("they are" vs "she is")
+    pub plural: bool,
+}
+
+/// Known pronoun sets, in subject/object/possessive/reflexive order
+const KNOWN_PRONOUNS: &[[&str; 4]] = &[
+    ["he", "him", "his", "himself"],
+    ["she", "her", "her", "herself"],
+    ["they", "them", "their", "themselves"],
+    ["it", "it", "its", "itself"],
+    ["xe", "xem", "xyr", "xemself"],
+    ["ze", "zir", "zir", "zirself"],
+    ["ze", "hir", "hir", "hirself"],
+    ["fae", "faer", "faer", "faerself"],
+    ["ey", "em", "eir", "emself"],
+    ["e", "em", "eir", "emself"],
+    ["ve", "ver", "vis", "verself"],
+    ["per", "per", "pers", "perself"],
+];
+
+impl Pronouns {
+    fn from_forms(forms: [&str; 4]) -> Self {
+        Self {
+            subject: forms[0].to_string(),
+            object: forms[1].to_string(),
+            possessive: forms[2].to_string(),
+            reflexive: forms[3].to_string(),
+            plural: forms[0] == "they",
+        }
+    }
+
+    /// The default pronouns used when a user hasn't set any, or they can't be understood
+    pub fn they() -> Self {
+        Self::from_forms(KNOWN_PRONOUNS[2])
+    }
+
+    /// Parse a free-form pronoun string as entered on a profile, e.g. "she/her", "he/they",
+    /// "xe/xem" or "any". The first set that can be understood wins, a full five-form set
+    /// ("bun/bun/buns/buns/bunself") is accepted as-is, and anything else falls back to they/them.
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim().to_lowercase();
+        let forms: Vec<&str> = input
+            .split(|c: char| c == '/' || c == ',' || c.is_whitespace())
+            .filter(|form| !form.is_empty())
+            .collect();
+
+        // "any", "any pronouns", "any/all" - they/them is the safest neutral choice
+        if forms.is_empty() || forms[0] == "any" || forms[0] == "all" {
+            return Self::they();
+        }
+
+        // A full set was given, trust the user knows their own pronouns best.
+        // The fourth form (possessive pronoun, e.g. "buns") isn't used when rendering
+        if forms.len() == 5 && !KNOWN_PRONOUNS.iter().any(|known| known[0] == forms[0]) {
+            return Self::from_forms([forms[0], forms[1], forms[2], forms[4]]);
+        }
+
+        // Prefer a set where both the subject and the object match (to tell ze/zir from ze/hir),
+        // then fall back to the first form that is a known subject
+        for form in &forms {
+            let mut candidates = KNOWN_PRONOUNS.iter().filter(|known| known[0] == *form);
+            let first = candidates.clone().next();
+
+            if let Some(known) = candidates.find(|known| forms.contains(&known[1])) {
+                return Self::from_forms(*known);
+            }
+
+            if let Some(known) = first {
+                return Self::from_forms(*known);
+            }
+        }
+
+        Self::they()
+    }
+
+    /// Get the pronouns for a profile's pronoun field, falling back to they/them when unset
+    pub fn from_profile(pronouns: Option<&str>) -> Self {
+        pronouns.map(Self::parse).unwrap_or_else(Self::they)
+    }
+
+    /// The subject pronoun with its first letter capitalized, for the start of a sentence
+    pub fn subject_capitalized(&self) -> String {
+        let mut chars = self.subject.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            None => String::new(),
+        }
+    }
+
+    /// Pick the verb form agreeing with these pronouns, e.g. `verb("is", "are")`
+    pub fn verb<'a>(&self, singular: &'a str, plural: &'a str) -> &'a str {
+        if self.plural {
+            plural
+        } else {
+            singular
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let cases = [
+            ("she/her", ["she", "her", "her", "herself"]),
+            ("He/Him", ["he", "him", "his", "himself"]),
+            ("he/they", ["he", "him", "his", "himself"]),
+            ("they, them", ["they", "them", "their", "themselves"]),
+            ("xe/xem", ["xe", "xem", "xyr", "xemself"]),
+            ("ze", ["ze", "zir", "zir", "zirself"]),
+            ("ze/zir", ["ze", "zir", "zir", "zirself"]),
+            ("ze/hir", ["ze", "hir", "hir", "hirself"]),
+            ("e/em/eir", ["e", "em", "eir", "emself"]),
+            (
+                "bun/bun/buns/buns/bunself",
+                ["bun", "bun", "buns", "bunself"],
+            ),
+            ("she/her/her/hers/herself", ["she", "her", "her", "herself"]),
+            ("any", ["they", "them", "their", "themselves"]),
+            ("all", ["they", "them", "their", "themselves"]),
+            ("Any pronouns", ["they", "them", "their", "themselves"]),
+            ("any/all", ["they", "them", "their", "themselves"]),
+            ("", ["they", "them", "their", "themselves"]),
+            (" / , ", ["they", "them", "their", "themselves"]),
+            ("ask me", ["they", "them", "their", "themselves"]),
+            ("bun/buns", ["they", "them", "their", "themselves"]),
+        ];
+
+        for (input, expected) in cases {
+            let parsed = Pronouns::parse(input);
+            assert_eq!(
+                [
+                    parsed.subject.as_str(),
+                    parsed.object.as_str(),
+                    parsed.possessive.as_str(),
+                    parsed.reflexive.as_str()
+                ],
+                expected,
+                "parsing {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn plural() {
+        let cases = [
+            ("they/them", true),
+            ("any", true),
+            ("she/her", false),
+            ("bun/bun/buns/buns/bunself", false),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                Pronouns::parse(input).plural,
+                expected,
+                "parsing {:?}",
+                input
+            );
+        }
+    }
+}
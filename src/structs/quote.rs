@@ -1,10 +1,68 @@
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
 
 #[derive(Debug)]
 pub struct Quote {
+    pub quote_id: i32,
     pub user_id: i64,
     pub username: String,
     pub quote: String,
     pub added_by: i64,
     pub added_at: OffsetDateTime,
+    pub guild_id: Option<i64>,
+    pub channel_id: Option<i64>,
+    pub message_id: Option<i64>,
+    pub attachments: Option<String>,
+    pub reply_username: Option<String>,
+    pub reply_content: Option<String>,
+    pub nickname: Option<String>,
+}
+
+impl Quote {
+    /// The name of the author as it was when the message was quoted
+    pub fn stored_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.username)
+    }
+
+    /// A link to the original message, if we know where it was sent
+    pub fn jump_link(&self) -> Option<String> {
+        let channel_id = self.channel_id?;
+        let message_id = self.message_id?;
+
+        Some(match self.guild_id {
+            Some(guild_id) => format!(
+                "https://discord.com/channels/{}/{}/{}",
+                guild_id, channel_id, message_id
+            ),
+            None => format!(
+                "https://discord.com/channels/@me/{}/{}",
+                channel_id, message_id
+            ),
+        })
+    }
+
+    /// The attachment URLs stored with the quote
+    pub fn attachment_urls(&self) -> Vec<&str> {
+        self.attachments
+            .as_deref()
+            .map(|attachments| attachments.lines().filter(|url| !url.is_empty()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Filters for listing quotes, any filter left as `None` matches everything
+#[derive(Debug, Default)]
+pub struct QuoteFilter {
+    pub user_id: Option<u64>,
+    pub added_by: Option<u64>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+    pub channel_id: Option<u64>,
+}
+
+/// The quote picked for a given day
+#[derive(Debug)]
+pub struct QuoteOfTheDay {
+    pub day: Date,
+    pub quote_id: Option<i32>,
+    pub message_id: Option<i64>,
 }
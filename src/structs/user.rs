@@ -1,7 +1,80 @@
+use time::OffsetDateTime;
+
 pub struct User {
     pub id: u64,
     pub discord_id: u64,
     pub actions_allowed: bool,
     pub about: Option<String>,
     pub pronouns: Option<String>,
+    pub quotes_allowed: QuotePermission,
+    /// Categories of actions this user doesn't want to receive, see `ActionDefinition::category`
+    pub blocked_action_categories: Vec<String>,
+}
+
+/// What an admin changed about a member's profile. Stored apart from the profile, so it outlives
+/// the profile being deleted
+#[derive(Debug, Default)]
+pub struct ProfileModeration {
+    /// Until when the about section and pronouns can't be edited
+    pub locked_until: Option<OffsetDateTime>,
+    /// Whether an admin turned actions off for the member, whatever they chose themselves
+    pub actions_forced_off: bool,
+}
+
+impl ProfileModeration {
+    /// Whether the about section and pronouns are currently locked from edits
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| locked_until > OffsetDateTime::now_utc())
+    }
+}
+
+/// Who a member allows to quote them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum QuotePermission {
+    #[name = "Nobody"]
+    Nobody,
+    #[name = "Vouched members only"]
+    VouchedOnly,
+    #[name = "Everyone"]
+    Everyone,
+}
+
+impl QuotePermission {
+    pub fn from_db(value: i8) -> Self {
+        match value {
+            0 => Self::Nobody,
+            1 => Self::VouchedOnly,
+            _ => Self::Everyone,
+        }
+    }
+
+    pub fn to_db(self) -> i8 {
+        match self {
+            Self::Nobody => 0,
+            Self::VouchedOnly => 1,
+            Self::Everyone => 2,
+        }
+    }
+}
+
+impl User {
+    /// Whether actions of a category can be performed on this user, as far as they're concerned.
+    /// An admin can still have turned actions off for them, see `ProfileModeration`
+    pub fn accepts_action_category(&self, category: &str) -> bool {
+        self.actions_allowed
+            && !self
+                .blocked_action_categories
+                .iter()
+                .any(|blocked| blocked == category)
+    }
+
+    /// Whether this user can be quoted by someone, given whether that someone has been vouched
+    pub fn accepts_quote_from(&self, quoter_is_vouched: bool) -> bool {
+        match self.quotes_allowed {
+            QuotePermission::Nobody => false,
+            QuotePermission::VouchedOnly => quoter_is_vouched,
+            QuotePermission::Everyone => true,
+        }
+    }
 }
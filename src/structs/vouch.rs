@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use chrono_tz::US::Central;
 use serenity::all::User;
+use time::OffsetDateTime;
 
 #[derive(Debug, Clone)]
 pub struct Vouch {
@@ -24,4 +25,26 @@ impl Vouch {
             .format("%Y-%m-%d %H:%M:%S")
             .to_string()
     }
+
+    /// Turn this pending vouch into a record ready to be stored, once approved
+    pub fn to_record(&self, approved_by: &User) -> VouchRecord {
+        VouchRecord {
+            user_id: self.user.id.into(),
+            vouched_by: self.vouched_by.id.into(),
+            approved_by: approved_by.id.into(),
+            vouched_at: OffsetDateTime::from_unix_timestamp(self.vouch_time.timestamp())
+                .unwrap_or_else(|_| OffsetDateTime::now_utc()),
+            approved_at: OffsetDateTime::now_utc(),
+        }
+    }
+}
+
+/// An approved vouch, as stored in the database
+#[derive(Debug)]
+pub struct VouchRecord {
+    pub user_id: i64,
+    pub vouched_by: i64,
+    pub approved_by: i64,
+    pub vouched_at: OffsetDateTime,
+    pub approved_at: OffsetDateTime,
 }
@@ -1,4 +1,10 @@
 use std::process::Command;
+use std::time::Duration;
+
+use poise::CreateReply;
+use time::{Date, Month};
+
+use crate::{Context, Error};
 
 pub async fn get_rustc_version() -> String {
     let rustc_version = Command::new("rustc")
@@ -9,22 +15,260 @@ pub async fn get_rustc_version() -> String {
     String::from_utf8(rustc_version.stdout).expect("failed to convert rustc version to string")
 }
 
-pub async fn get_random_action_image(action: String) -> Result<String, reqwest::Error> {
-    let action = action.to_lowercase();
-    let action = action.replace(" ", "_");
+/// Whether the author of the command has the admin role
+pub async fn has_admin_role(ctx: Context<'_>) -> bool {
+    match ctx.author_member().await {
+        Some(member) => member
+            .roles
+            .iter()
+            .any(|role_id| *role_id == ctx.data().config.roles.admin),
+        None => false,
+    }
+}
+
+/// Command check ensuring the author has the admin role, replying with an error if they don't
+pub async fn is_admin(ctx: Context<'_>) -> Result<bool, Error> {
+    let is_admin = has_admin_role(ctx).await;
+
+    if !is_admin {
+        ctx.send(
+            CreateReply::default()
+                .content(":x: You must be an admin to use this command!")
+                .ephemeral(true),
+        )
+        .await?;
+    }
+
+    Ok(is_admin)
+}
+
+/// Parse a human duration such as "30m", "12h", "7d" or "1d12h" into a `Duration`
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let mut total = 0u64;
+    let mut number = String::new();
+
+    for c in input.trim().to_lowercase().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let multiplier = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 60 * 60 * 24,
+            'w' => 60 * 60 * 24 * 7,
+            _ => return None,
+        };
+
+        // Absurdly long durations are rejected rather than overflowing
+        let seconds = number.parse::<u64>().ok()?.checked_mul(multiplier)?;
+        total = total.checked_add(seconds)?;
+        number.clear();
+    }
+
+    // Trailing numbers without a unit are ambiguous, as is a zero duration
+    if !number.is_empty() || total == 0 {
+        return None;
+    }
+
+    Some(Duration::from_secs(total))
+}
+
+/// Normalize text for comparison: lowercase, punctuation removed and whitespace collapsed
+fn normalize_text(text: &str) -> Vec<char> {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect()
+}
+
+/// Texts shorter than this after normalization, e.g. a lone emoji or "lol", say too little to
+/// be compared
+const MIN_COMPARABLE_LENGTH: usize = 4;
+
+/// How similar two pieces of text are, from 0.0 (nothing in common) to 1.0 (identical after
+/// normalization), based on the Levenshtein edit distance. `None` if either text is too short to
+/// tell, which includes text that is only punctuation or emoji
+pub fn text_similarity(a: &str, b: &str) -> Option<f64> {
+    let a = normalize_text(a);
+    let b = normalize_text(b);
+
+    if a.len() < MIN_COMPARABLE_LENGTH || b.len() < MIN_COMPARABLE_LENGTH {
+        return None;
+    }
+    let longest = a.len().max(b.len());
+
+    // Classic two-row dynamic programming edit distance
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    Some(1.0 - previous[b.len()] as f64 / longest as f64)
+}
+
+/// Parse a date in the YYYY-MM-DD format
+pub fn parse_date(input: &str) -> Option<Date> {
+    let mut parts = input.trim().splitn(3, '-');
+    let year = parts.next()?.parse::<i32>().ok()?;
+    let month = Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day = parts.next()?.parse::<u8>().ok()?;
+
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+/// Find the first link to a Discord message in `text`, returning its channel and message ids
+pub fn find_message_link(text: &str) -> Option<(u64, u64)> {
+    text.match_indices("/channels/")
+        .find_map(|(start, prefix)| {
+            let host = &text[..start];
+            if !host.ends_with("discord.com") && !host.ends_with("discordapp.com") {
+                return None;
+            }
+
+            // The path is /channels/<guild>/<channel>/<message>, the link may be followed by more text
+            let mut parts = text[start + prefix.len()..].splitn(3, '/');
+            let _guild = parts.next()?;
+            let channel = parts.next()?.parse().ok().filter(|&id: &u64| id != 0)?;
+            let message = parts.next()?;
+            let end = message
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(message.len());
+
+            let message = message[..end].parse().ok().filter(|&id: &u64| id != 0)?;
+
+            Some((channel, message))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_cases() {
+        let cases = [
+            ("30s", Some(30)),
+            ("30m", Some(30 * 60)),
+            ("12h", Some(12 * 60 * 60)),
+            ("7d", Some(7 * 24 * 60 * 60)),
+            ("2w", Some(2 * 7 * 24 * 60 * 60)),
+            ("1d12h", Some(36 * 60 * 60)),
+            (" 1H30M ", Some(90 * 60)),
+            ("", None),
+            ("30", None),
+            ("1h30", None),
+            ("h", None),
+            ("0m", None),
+            ("5y", None),
+            ("-5m", None),
+            ("1.5h", None),
+            ("99999999999999999999s", None),
+            ("40000000000000w", None),
+            ("18446744073709551615s1s", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_duration(input),
+                expected.map(Duration::from_secs),
+                "parsing {:?}",
+                input
+            );
+        }
+    }
 
-    let url = format!("https://api.otakugifs.xyz/gif?reaction={}", action);
-    let response = reqwest::get(&url)
-        .await
-        .expect("failed to get random action image");
+    #[test]
+    fn text_similarity_cases() {
+        let cases = [
+            ("hello world", "hello world", Some(1.0)),
+            ("Hello, World!", "hello world", Some(1.0)),
+            ("hello   world", " hello world ", Some(1.0)),
+            ("kitten", "sitting", Some(1.0 - 3.0 / 7.0)),
+            ("abcd", "wxyz", Some(0.0)),
+            ("i love this server", "i love this server!!", Some(1.0)),
+            (
+                "i love this server",
+                "i love that server",
+                Some(1.0 - 2.0 / 18.0),
+            ),
+            ("", "", None),
+            ("!!!", "", None),
+            ("!!!", "???", None),
+            ("\u{1F602}", "\u{1F602}", None),
+            ("lol", "lol", None),
+            ("hello", "", None),
+            ("hello", "hi", None),
+        ];
 
-    let response_json: serde_json::Value =
-        serde_json::from_str(&response.text().await.expect("failed to get response text"))
-            .expect("failed to parse response json");
+        for (a, b, expected) in cases {
+            let similarity = text_similarity(a, b);
+            match (similarity, expected) {
+                (Some(similarity), Some(expected)) => assert!(
+                    (similarity - expected).abs() < 1e-9,
+                    "similarity of {:?} and {:?} is {}, expected {}",
+                    a,
+                    b,
+                    similarity,
+                    expected
+                ),
+                (None, None) => {}
+                _ => panic!(
+                    "similarity of {:?} and {:?} is {:?}, expected {:?}",
+                    a, b, similarity, expected
+                ),
+            }
+            assert_eq!(similarity, text_similarity(b, a), "{:?} and {:?}", a, b);
+        }
+    }
 
-    let image_url = response_json["url"]
-        .as_str()
-        .expect("failed to get image url from response json");
+    #[test]
+    fn find_message_link_cases() {
+        let cases = [
+            ("https://discord.com/channels/1/2/3", Some((2, 3))),
+            (
+                "[Jump to message](https://discord.com/channels/1/2/3)",
+                Some((2, 3)),
+            ),
+            (
+                "https://ptb.discordapp.com/channels/1/2/3 and more",
+                Some((2, 3)),
+            ),
+            ("https://discord.com/channels/@me/2/3", Some((2, 3))),
+            (
+                "https://example.com/channels/1/2/3 https://discord.com/channels/1/4/5",
+                Some((4, 5)),
+            ),
+            ("https://discord.com/channels/1/2", None),
+            ("https://discord.com/channels/1/2/", None),
+            ("https://discord.com/channels/1/two/3", None),
+            ("https://discord.com/channels/1/0/3", None),
+            ("https://discord.com/channels/1/2/0", None),
+            ("https://example.com/channels/1/2/3", None),
+            ("no links here", None),
+        ];
 
-    Ok(image_url.to_string())
+        for (text, expected) in cases {
+            assert_eq!(
+                find_message_link(text),
+                expected,
+                "finding a link in {:?}",
+                text
+            );
+        }
+    }
 }